@@ -1,49 +1,286 @@
 use actix_web::{
-    delete,
-    get,
-    patch,
-    post,
-    web,
+    delete, get,
+    http::header::{
+        ContentDisposition, DispositionParam, DispositionType, EntityTag, HeaderName, HeaderValue,
+        IfMatch, ETAG, LINK, WARNING,
+    },
+    patch, post, web,
     web::{Json, Path},
-    // HttpResponse,
+    HttpResponse, ResponseError,
 };
-use surrealdb::sql::Thing;
+use chrono::Utc;
+use serde_json::{Map, Value};
 
+use crate::audit::Actor;
 use crate::db::*;
 use crate::prelude::*;
+use crate::reminders::get_reminders;
+use crate::Db;
 use types::*;
 
 #[post("/inv")]
-pub async fn create(inv: web::Json<Investment>) -> Result<Json<Investment>> {
+pub async fn create(
+    db: web::Data<Db>,
+    actor: Actor,
+    inv: web::Json<Investment>,
+) -> Result<Json<Investment>> {
     let mut inv = inv.into_inner();
-    let todo = add_inv(&mut inv).await?;
+    validation::validate(&inv).map_err(Error::Validation)?;
+    let todo = add_inv(&db, &actor, &mut inv).await?;
     Ok(Json(todo))
 }
 
+/// Responds with the investment and its version as the `ETag`.
+fn with_etag(inv: Investment) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((ETAG, EntityTag::new_strong(inv.version.to_string())))
+        .json(inv)
+}
+
+/// The version an `If-Match` header asks for; `*` matches any.
+fn expected_version(if_match: &IfMatch) -> Result<Option<u64>> {
+    match if_match {
+        IfMatch::Any => Ok(None),
+        IfMatch::Items(tags) => match tags.first() {
+            Some(tag) => tag.tag().parse().map(Some).map_err(|_| {
+                Error::BadRequest(format!("'{}' is not a version of an investment", tag.tag()))
+            }),
+            None => Ok(None),
+        },
+    }
+}
+
 #[get("/inv/{id}")]
-pub async fn get(id: Path<String>) -> Result<Json<Investment>> {
-    let task = get_inv(id.into_inner()).await?;
+pub async fn get(db: web::Data<Db>, id: Path<String>) -> Result<HttpResponse> {
+    let task = get_inv(&db, id.into_inner()).await?;
+
+    Ok(with_etag(task))
+}
+
+/// Marks the response of a deprecated route, pointing at the route that
+/// replaces it. Errors are marked too, so that clients notice either way.
+fn deprecated(result: Result<HttpResponse>, successor: &str) -> HttpResponse {
+    log::warn!("deprecated route used, clients should move to {successor}");
+
+    let mut response = result.unwrap_or_else(|e| e.error_response());
+    let headers = response.headers_mut();
+    headers.insert(
+        HeaderName::from_static("deprecation"),
+        HeaderValue::from_static("true"),
+    );
+    if let Ok(link) = HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\"")) {
+        headers.insert(LINK, link);
+    }
+    if let Ok(warning) = HeaderValue::from_str(&format!(
+        "299 - \"Deprecated route, use {successor} instead\""
+    )) {
+        headers.insert(WARNING, warning);
+    }
+
+    response
+}
+
+async fn patch_inv(
+    db: &Db,
+    actor: &Actor,
+    id: String,
+    if_match: Option<web::Header<IfMatch>>,
+    patch: Map<String, Value>,
+) -> Result<HttpResponse> {
+    let expected = match if_match {
+        Some(if_match) => expected_version(&if_match)?,
+        None => None,
+    };
+    let updated = update_inv(db, actor, id, patch, expected).await?;
+
+    Ok(with_etag(updated))
+}
 
-    Ok(Json(task))
+/// Takes a JSON merge patch with the fields to update.
+#[patch("/inv/{id}")]
+pub async fn update(
+    db: web::Data<Db>,
+    actor: Actor,
+    id: Path<String>,
+    if_match: Option<web::Header<IfMatch>>,
+    patch: web::Json<Map<String, Value>>,
+) -> Result<HttpResponse> {
+    patch_inv(&db, &actor, id.into_inner(), if_match, patch.into_inner()).await
 }
 
+#[delete("/inv/{id}")]
+pub async fn delete(db: web::Data<Db>, actor: Actor, id: Path<String>) -> Result<Json<Record>> {
+    let deleted = delete_inv(&db, &actor, id.into_inner()).await?;
+
+    Ok(Json(deleted))
+}
+
+/// Deprecated in favour of `PATCH /inv/{id}`: takes the `id` in the patch.
 #[patch("/inv")]
-pub async fn update(inv: web::Json<Investment>) -> Result<Json<Investment>> {
+pub async fn legacy_update(
+    db: web::Data<Db>,
+    actor: Actor,
+    if_match: Option<web::Header<IfMatch>>,
+    patch: web::Json<Map<String, Value>>,
+) -> HttpResponse {
+    let mut patch = patch.into_inner();
+    match take_patch_id(&mut patch) {
+        Ok(id) => {
+            let successor = format!("/inv/{id}");
+            let result = patch_inv(&db, &actor, id, if_match, patch).await;
+            deprecated(result, &successor)
+        }
+        Err(e) => deprecated(Err(e), "/inv/{id}"),
+    }
+}
+
+/// Deprecated in favour of `DELETE /inv/{id}`: takes the id as the body.
+#[delete("/inv")]
+pub async fn legacy_delete(
+    db: web::Data<Db>,
+    actor: Actor,
+    id: web::Json<RecordId>,
+) -> HttpResponse {
+    let id = id.into_inner().to_string();
+    let successor = format!("/inv/{id}");
+    let result = delete_inv(&db, &actor, id)
+        .await
+        .map(|deleted| HttpResponse::Ok().json(deleted));
+
+    deprecated(result, &successor)
+}
+
+#[post("/inv/{id}/restore")]
+pub async fn restore(
+    db: web::Data<Db>,
+    actor: Actor,
+    id: Path<String>,
+) -> Result<Json<Investment>> {
+    let restored = restore_inv(&db, &actor, id.into_inner()).await?;
+
+    Ok(Json(restored))
+}
+
+#[delete("/inv/{id}/purge")]
+pub async fn purge(db: web::Data<Db>, actor: Actor, id: Path<String>) -> Result<Json<Record>> {
+    let purged = purge_inv(&db, &actor, id.into_inner()).await?;
+
+    Ok(Json(purged))
+}
+
+#[post("/inv/{id}/renew")]
+pub async fn renew(
+    db: web::Data<Db>,
+    actor: Actor,
+    id: Path<String>,
+    inv: web::Json<Investment>,
+) -> Result<Json<Renewal>> {
     let mut inv = inv.into_inner();
-    let updated = update_inv(&mut inv).await?;
+    validation::validate(&inv).map_err(Error::Validation)?;
+    let renewal = renew_inv(&db, &actor, id.into_inner(), &mut inv).await?;
 
-    Ok(Json(updated))
+    Ok(Json(renewal))
 }
 
-#[delete("/inv")]
-pub async fn delete(id: web::Json<Thing>) -> Result<Json<Record>> {
-    let deleted = delete_inv(id.into_inner()).await?;
+#[get("/inv/{id}/chain")]
+pub async fn chain(db: web::Data<Db>, id: Path<String>) -> Result<Json<RenewalChain>> {
+    let chain = get_chain(&db, id.into_inner()).await?;
 
-    Ok(Json(deleted))
+    Ok(Json(chain))
+}
+
+#[get("/inv/{id}/history")]
+pub async fn history(db: web::Data<Db>, id: Path<String>) -> Result<Json<Vec<AuditEntry>>> {
+    let history = get_history(&db, id.into_inner()).await?;
+
+    Ok(Json(history))
 }
 
 #[get("/invs")]
-pub async fn list() -> Result<Json<Vec<Investment>>> {
-    let todos = get_all_invs().await?;
-    Ok(Json(todos))
+pub async fn list(
+    db: web::Data<Db>,
+    query: web::Query<ListQuery>,
+) -> Result<Json<Page<Investment>>> {
+    let page = get_all_invs(&db, &query).await?;
+    Ok(Json(page))
+}
+
+/// Every investment matching the filters of `GET /invs` as a file to
+/// download, in the format given by `format`.
+#[get("/invs/export")]
+pub async fn export(
+    db: web::Data<Db>,
+    query: web::Query<ListQuery>,
+    export: web::Query<ExportQuery>,
+) -> Result<HttpResponse> {
+    let invs = export_invs(&db, &query).await?;
+    let format = export.format;
+    let body = crate::export::write(format, &invs)?;
+    let filename = format!(
+        "investments-{}.{}",
+        Utc::now().format("%Y-%m-%d"),
+        format.as_str()
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(filename)],
+        })
+        .body(body))
+}
+
+#[get("/invs/calendar.ics")]
+pub async fn calendar(db: web::Data<Db>, query: web::Query<ListQuery>) -> Result<HttpResponse> {
+    let invs = export_invs(&db, &query).await?;
+    let body = crate::calendar::write(&invs, Utc::now());
+
+    Ok(HttpResponse::Ok()
+        .content_type(crate::calendar::CONTENT_TYPE)
+        .body(body))
+}
+
+#[post("/invs/bulk")]
+pub async fn bulk(
+    db: web::Data<Db>,
+    actor: Actor,
+    request: web::Json<BulkRequest>,
+) -> Result<Json<BulkReport>> {
+    let report = bulk_invs(&db, &actor, request.into_inner().operations).await?;
+    Ok(Json(report))
+}
+
+#[post("/invs/import")]
+pub async fn import(
+    db: web::Data<Db>,
+    actor: Actor,
+    request: web::Json<ImportRequest>,
+) -> Result<Json<ImportReport>> {
+    let report = import_invs(&db, &actor, &request).await?;
+    Ok(Json(report))
+}
+
+#[get("/invs/trash")]
+pub async fn trash(db: web::Data<Db>) -> Result<Json<Vec<Investment>>> {
+    let invs = get_trash(&db).await?;
+    Ok(Json(invs))
+}
+
+#[get("/reminders")]
+pub async fn reminders(db: web::Data<Db>) -> Result<Json<Vec<Reminder>>> {
+    let reminders = get_reminders(&db).await?;
+    Ok(Json(reminders))
+}
+
+#[get("/invs/summary")]
+pub async fn summary(db: web::Data<Db>) -> Result<Json<PortfolioSummary>> {
+    let summary = get_summary(&db).await?;
+    Ok(Json(summary))
+}
+
+#[get("/invs/analytics")]
+pub async fn analytics(db: web::Data<Db>) -> Result<Json<PortfolioAnalytics>> {
+    let analytics = get_analytics(&db).await?;
+    Ok(Json(analytics))
 }
@@ -0,0 +1,59 @@
+//! Audit log of the changes made to investments, kept in the `audit` table.
+
+use std::convert::Infallible;
+use std::future::{ready, Ready};
+
+use actix_web::{dev::Payload, FromRequest, HttpRequest};
+use chrono::Utc;
+
+use crate::prelude::*;
+use crate::Db;
+use types::*;
+
+pub(crate) const AUDIT: &str = "audit";
+
+/// Header naming who makes a request.
+const ACTOR_HEADER: &str = "X-Actor";
+
+/// Who is making a change, taken from the `X-Actor` header. The API has no
+/// users of its own, so requests without one are made by "anonymous".
+#[derive(Clone, PartialEq, Debug)]
+pub struct Actor(pub String);
+
+impl FromRequest for Actor {
+    type Error = Infallible;
+    type Future = Ready<std::result::Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let actor = req
+            .headers()
+            .get(ACTOR_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|actor| !actor.is_empty())
+            .unwrap_or("anonymous");
+
+        ready(Ok(Actor(actor.to_string())))
+    }
+}
+
+/// Records that `actor` did `action` to the investment `id`.
+pub(crate) async fn record(
+    db: &Db,
+    actor: &Actor,
+    action: AuditAction,
+    id: &RecordId,
+    changes: Vec<FieldChange>,
+) -> Result<()> {
+    let entry = AuditEntry {
+        id: None,
+        investment: id.clone(),
+        action,
+        actor: actor.0.clone(),
+        changes,
+        at: Utc::now(),
+    };
+    let _: Vec<AuditEntry> = db.create(AUDIT).content(entry).await?;
+
+    Ok(())
+}
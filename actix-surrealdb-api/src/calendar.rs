@@ -0,0 +1,101 @@
+//! Writing the maturities of investments as the iCalendar feed of
+//! `GET /invs/calendar.ics` (RFC 5545).
+
+use chrono::{DateTime, Utc};
+
+use types::*;
+
+pub const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+/// Longest line allowed, in bytes, before it has to be folded.
+const MAX_LINE: usize = 75;
+
+/// An all-day event on the end date of each of `invs`, stamped with `now`.
+/// Investments without an id or an end date are left out.
+pub fn write(invs: &[Investment], now: DateTime<Utc>) -> String {
+    let mut ics = String::new();
+    let mut line = |line: String| fold(&mut ics, &line);
+
+    line("BEGIN:VCALENDAR".into());
+    line("VERSION:2.0".into());
+    line("PRODID:-//mone-goblin//Investments//EN".into());
+    line("CALSCALE:GREGORIAN".into());
+    line("METHOD:PUBLISH".into());
+    line("X-WR-CALNAME:Maturing investments".into());
+    for inv in invs {
+        let (Some(id), Some(end_date)) = (&inv.id, inv.end_date) else {
+            continue;
+        };
+        let day = end_date.date_naive();
+        let next_day = day.succ_opt().unwrap_or(day);
+
+        line("BEGIN:VEVENT".into());
+        line(format!("UID:{id}@mone-goblin"));
+        line(format!("DTSTAMP:{}", timestamp(now)));
+        line(format!("DTSTART;VALUE=DATE:{}", day.format("%Y%m%d")));
+        line(format!("DTEND;VALUE=DATE:{}", next_day.format("%Y%m%d")));
+        line(format!(
+            "SUMMARY:{}",
+            escape(&format!("{} of {} matures", inv.inv_name, inv.name))
+        ));
+        line(format!(
+            "DESCRIPTION:{}",
+            escape(&format!(
+                "₹{} back on ₹{} invested at {}% ({}, {}).",
+                inv.return_amount.to_indian_string(),
+                inv.inv_amount.to_indian_string(),
+                inv.return_rate,
+                inv.inv_type,
+                inv.return_type,
+            ))
+        ));
+        if !inv.tags.is_empty() {
+            let tags: Vec<String> = inv.tags.iter().map(|tag| escape(tag)).collect();
+            line(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        if let Some(updated_at) = inv.updated_at {
+            line(format!("LAST-MODIFIED:{}", timestamp(updated_at)));
+        }
+        line("END:VEVENT".into());
+    }
+    line("END:VCALENDAR".into());
+
+    ics
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escapes the characters with a meaning in text values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Appends `line` to `ics`, folded into lines of at most [`MAX_LINE`] bytes,
+/// each continuation starting with a space.
+fn fold(ics: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE {
+            ics.push_str("\r\n ");
+            width = 1;
+        }
+        ics.push(c);
+        width += c.len_utf8();
+    }
+    ics.push_str("\r\n");
+}
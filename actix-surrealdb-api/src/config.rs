@@ -0,0 +1,397 @@
+//! Server configuration.
+//!
+//! Settings are layered, each layer overriding the previous one:
+//! 1. the defaults below, which keep the data in memory
+//! 2. a TOML file, `config.toml` unless `--config`/`MONE_CONFIG` says otherwise
+//! 3. `MONE_*` environment variables
+//! 4. command line flags
+//!
+//! See `config.example.toml` for every setting.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use serde::Deserialize;
+use thiserror::Error;
+
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    #[error("could not read {path}: {source}")]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("could not parse {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub server: ServerConfig,
+    pub reminders: RemindersConfig,
+    pub maturity: MaturityConfig,
+    /// Filter in the `env_logger` syntax, e.g. `info` or `info,actix_web=warn`.
+    pub log_level: String,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// Where the data lives, see [`DatabaseConfig::address`].
+    pub endpoint: String,
+    pub username: String,
+    pub password: String,
+    pub namespace: String,
+    pub database: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub host: String,
+    pub port: u16,
+    /// Origins allowed to call the API; empty or `*` allows any origin.
+    pub cors_origins: Vec<String>,
+}
+
+/// Where reminders of maturing investments are delivered.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifierKind {
+    /// Written to the server log.
+    #[default]
+    Log,
+    /// POSTed as JSON to `webhook_url`.
+    Webhook,
+    /// Mailed through the SMTP server of `[reminders.email]`.
+    Email,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RemindersConfig {
+    pub enabled: bool,
+    /// Days ahead of the end date of an investment to remind of it.
+    pub days_before: Vec<u32>,
+    /// How often to look for maturing investments, in seconds.
+    pub interval_secs: u64,
+    pub notifier: NotifierKind,
+    pub webhook_url: String,
+    pub email: EmailConfig,
+}
+
+/// The task marking active investments past their end date as matured.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MaturityConfig {
+    pub enabled: bool,
+    /// How often to look for investments that have matured, in seconds.
+    pub interval_secs: u64,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    /// Upgrade the connection with STARTTLS; only turn off for local servers.
+    pub starttls: bool,
+    /// Left empty for servers that need no authentication.
+    pub username: String,
+    pub password: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            database: DatabaseConfig::default(),
+            server: ServerConfig::default(),
+            reminders: RemindersConfig::default(),
+            maturity: MaturityConfig::default(),
+            log_level: "info".to_string(),
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            endpoint: "mem://".to_string(),
+            username: "root".to_string(),
+            password: "root".to_string(),
+            namespace: "namespace".to_string(),
+            database: "database".to_string(),
+        }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            host: "localhost".to_string(),
+            port: 8080,
+            cors_origins: Vec::new(),
+        }
+    }
+}
+
+impl Default for RemindersConfig {
+    fn default() -> Self {
+        RemindersConfig {
+            enabled: true,
+            days_before: types::DEFAULT_REMINDER_DAYS.to_vec(),
+            interval_secs: 3600,
+            notifier: NotifierKind::default(),
+            webhook_url: String::new(),
+            email: EmailConfig::default(),
+        }
+    }
+}
+
+impl Default for MaturityConfig {
+    fn default() -> Self {
+        MaturityConfig {
+            enabled: true,
+            interval_secs: 3600,
+        }
+    }
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        EmailConfig {
+            smtp_host: "localhost".to_string(),
+            smtp_port: 587,
+            starttls: true,
+            username: String::new(),
+            password: String::new(),
+            from: String::new(),
+            to: String::new(),
+        }
+    }
+}
+
+/// API server of mone-goblin.
+///
+/// Every flag can also be set with the environment variable shown next to it.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Args {
+    /// TOML file to read the configuration from [default: config.toml, if it exists]
+    #[arg(long, env = "MONE_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// SurrealDB endpoint, e.g. ws://localhost:8000, mem:// or rocksdb://data
+    #[arg(long, env = "MONE_DB_ENDPOINT")]
+    db_endpoint: Option<String>,
+
+    #[arg(long, env = "MONE_DB_USERNAME")]
+    db_username: Option<String>,
+
+    #[arg(long, env = "MONE_DB_PASSWORD", hide_env_values = true)]
+    db_password: Option<String>,
+
+    #[arg(long, env = "MONE_DB_NAMESPACE")]
+    db_namespace: Option<String>,
+
+    #[arg(long, env = "MONE_DB_DATABASE")]
+    db_database: Option<String>,
+
+    /// Address to listen on
+    #[arg(long, env = "MONE_HOST")]
+    host: Option<String>,
+
+    #[arg(long, env = "MONE_PORT")]
+    port: Option<u16>,
+
+    /// Origins allowed by CORS, comma separated
+    #[arg(long, env = "MONE_CORS_ORIGINS", value_delimiter = ',')]
+    cors_origins: Option<Vec<String>>,
+
+    /// Whether to send reminders of maturing investments
+    #[arg(long, env = "MONE_REMINDERS_ENABLED")]
+    reminders_enabled: Option<bool>,
+
+    /// Where to deliver reminders
+    #[arg(long, env = "MONE_REMINDERS_NOTIFIER", value_enum)]
+    reminders_notifier: Option<NotifierKind>,
+
+    /// URL the webhook notifier POSTs reminders to
+    #[arg(long, env = "MONE_REMINDERS_WEBHOOK_URL")]
+    reminders_webhook_url: Option<String>,
+
+    #[arg(long, env = "MONE_SMTP_PASSWORD", hide_env_values = true)]
+    smtp_password: Option<String>,
+
+    /// Whether to mark investments past their end date as matured
+    #[arg(long, env = "MONE_MATURITY_ENABLED")]
+    maturity_enabled: Option<bool>,
+
+    /// Log filter, e.g. info or debug,actix_web=info
+    #[arg(long, env = "MONE_LOG_LEVEL")]
+    log_level: Option<String>,
+}
+
+impl Config {
+    /// Loads the configuration from the file, environment and command line.
+    pub fn load() -> Result<Config, ConfigError> {
+        let args = Args::parse();
+
+        let mut config = match &args.config {
+            Some(path) => Config::from_file(path)?,
+            None if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                Config::from_file(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            None => Config::default(),
+        };
+        config.apply(args);
+
+        Ok(config)
+    }
+
+    fn from_file(path: &Path) -> Result<Config, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Overrides the settings given as flags or environment variables.
+    fn apply(&mut self, args: Args) {
+        fn set<T>(setting: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *setting = value;
+            }
+        }
+
+        set(&mut self.database.endpoint, args.db_endpoint);
+        set(&mut self.database.username, args.db_username);
+        set(&mut self.database.password, args.db_password);
+        set(&mut self.database.namespace, args.db_namespace);
+        set(&mut self.database.database, args.db_database);
+        set(&mut self.server.host, args.host);
+        set(&mut self.server.port, args.port);
+        set(&mut self.server.cors_origins, args.cors_origins);
+        set(&mut self.reminders.enabled, args.reminders_enabled);
+        set(&mut self.reminders.notifier, args.reminders_notifier);
+        set(&mut self.reminders.webhook_url, args.reminders_webhook_url);
+        set(&mut self.reminders.email.password, args.smtp_password);
+        set(&mut self.maturity.enabled, args.maturity_enabled);
+        set(&mut self.log_level, args.log_level);
+    }
+}
+
+impl DatabaseConfig {
+    /// Address to connect to: a remote server (`ws://`, `wss://`, `http://`,
+    /// `https://`) or an embedded engine (`mem://`, `rocksdb://path`,
+    /// `speedb://path`), each embedded engine needing its cargo feature.
+    ///
+    /// An endpoint without a scheme, like `localhost:8000`, is a remote server
+    /// reached over WebSocket.
+    pub fn address(&self) -> String {
+        if self.endpoint.contains("://") {
+            self.endpoint.clone()
+        } else {
+            format!("ws://{}", self.endpoint)
+        }
+    }
+
+    /// Whether the database is a server we have to sign in to, as opposed to
+    /// an embedded engine running in this process.
+    pub fn is_remote(&self) -> bool {
+        let address = self.address();
+        ["ws://", "wss://", "http://", "https://"]
+            .iter()
+            .any(|scheme| address.starts_with(scheme))
+    }
+
+    /// Whether the data is lost when the server stops.
+    pub fn is_in_memory(&self) -> bool {
+        self.address().starts_with("mem://")
+    }
+}
+
+impl fmt::Debug for DatabaseConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DatabaseConfig")
+            .field("endpoint", &self.endpoint)
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .field("namespace", &self.namespace)
+            .field("database", &self.database)
+            .finish()
+    }
+}
+
+impl fmt::Debug for EmailConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailConfig")
+            .field("smtp_host", &self.smtp_host)
+            .field("smtp_port", &self.smtp_port)
+            .field("starttls", &self.starttls)
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .finish()
+    }
+}
+
+impl ServerConfig {
+    pub fn allows_any_origin(&self) -> bool {
+        self.cors_origins.is_empty() || self.cors_origins.iter().any(|origin| origin == "*")
+    }
+}
+
+/// Shows the effective configuration, without the password.
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let db = &self.database;
+        let server = &self.server;
+        let cors_origins = if server.allows_any_origin() {
+            "*".to_string()
+        } else {
+            server.cors_origins.join(", ")
+        };
+
+        writeln!(
+            f,
+            "database: {} as {} (password redacted), ns {}, db {}",
+            db.endpoint, db.username, db.namespace, db.database
+        )?;
+        writeln!(f, "server: {}:{}", server.host, server.port)?;
+        writeln!(f, "cors origins: {cors_origins}")?;
+        let reminders = &self.reminders;
+        if reminders.enabled {
+            writeln!(
+                f,
+                "reminders: {:?} days ahead, every {}s, via {:?}",
+                reminders.days_before, reminders.interval_secs, reminders.notifier
+            )?;
+        } else {
+            writeln!(f, "reminders: off")?;
+        }
+        if self.maturity.enabled {
+            writeln!(f, "maturity: every {}s", self.maturity.interval_secs)?;
+        } else {
+            writeln!(f, "maturity: off")?;
+        }
+        write!(f, "log level: {}", self.log_level)
+    }
+}
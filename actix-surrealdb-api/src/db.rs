@@ -1,57 +1,859 @@
-use chrono::offset::Utc;
+use std::collections::{HashSet, VecDeque};
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::Deserialize;
+use serde_json::{Map, Value};
 use surrealdb::sql::Thing;
 
+use crate::audit::{self, Actor, AUDIT};
+use crate::import;
 use crate::prelude::*;
-use crate::DB;
+use crate::Db;
 use types::*;
 
 const INVESTMENT: &str = "investment";
 
-pub async fn add_inv(inv: &mut Investment) -> Result<Investment> {
+/// Flags a record whose stored return amount is off from the computed one.
+fn check_return_amount(mut inv: Investment) -> Investment {
+    inv.return_mismatch = inv.return_mismatch();
+    inv
+}
+
+/// Parses an id like `investment:abc`, rejecting ids of other tables.
+fn parse_id(id: &str) -> Result<RecordId> {
+    let id = id
+        .parse()
+        .map_err(|_| Error::BadRequest(format!("'{id}' is not a valid investment id")))?;
+    check_id(&id)?;
+
+    Ok(id)
+}
+
+fn check_id(id: &RecordId) -> Result<()> {
+    if id.tb == INVESTMENT {
+        Ok(())
+    } else {
+        Err(Error::BadRequest(format!(
+            "'{id}' is not a valid investment id"
+        )))
+    }
+}
+
+/// The SurrealDB record an id refers to.
+fn thing(id: &RecordId) -> Thing {
+    Thing::from((id.tb.as_str(), id.id.as_str()))
+}
+
+fn not_found(id: &RecordId) -> Error {
+    Error::NotFound(format!("investment '{id}' does not exist"))
+}
+
+/// Sets the fields the API manages on an investment about to be created.
+/// Every investment starts active: its status only changes through the
+/// lifecycle afterwards.
+fn prepare_new(inv: &mut Investment) {
     inv.id = None;
+    inv.inv_status = None;
+    inv.return_mismatch = None;
+    inv.deleted_at = None;
+    inv.version = 1;
+    inv.tags = normalize_tags(std::mem::take(&mut inv.tags));
     inv.created_at = Some(Utc::now());
     inv.updated_at = Some(Utc::now());
-    let created: Vec<Investment> = DB.create(INVESTMENT).content(inv).await?;
+}
+
+pub async fn add_inv(db: &Db, actor: &Actor, inv: &mut Investment) -> Result<Investment> {
+    prepare_new(inv);
+    let mut created: Vec<Investment> = db.create(INVESTMENT).content(inv).await?;
+    let created = created
+        .pop()
+        .ok_or_else(|| Error::Internal("the investment was not created".into()))?;
+
+    if let Some(id) = &created.id {
+        let changes = FieldChange::diff(None, &created);
+        audit::record(db, actor, AuditAction::Create, id, changes).await?;
+    }
+
+    Ok(check_return_amount(created))
+}
+
+/// The investment `id`, if it exists and is not in the trash.
+async fn live_inv(db: &Db, id: &RecordId) -> Result<Investment> {
+    let inv: Option<Investment> = db.select(thing(id)).await?;
+    inv.filter(|inv| inv.deleted_at.is_none())
+        .ok_or_else(|| not_found(id))
+}
+
+/// The investment `id`, if it is in the trash.
+async fn trashed_inv(db: &Db, id: &RecordId) -> Result<Investment> {
+    let inv: Option<Investment> = db.select(thing(id)).await?;
+    inv.filter(|inv| inv.deleted_at.is_some())
+        .ok_or_else(|| Error::NotFound(format!("investment '{id}' is not in the trash")))
+}
+
+pub async fn get_inv(db: &Db, id: String) -> Result<Investment> {
+    let id = parse_id(&id)?;
+    let rec = live_inv(db, &id).await?;
+
+    Ok(check_return_amount(rec))
+}
+
+/// Moves the investment `id` to the trash, from where it can be restored or
+/// purged.
+pub async fn delete_inv(db: &Db, actor: &Actor, id: String) -> Result<Record> {
+    let id = parse_id(&id)?;
+    let before = live_inv(db, &id).await?;
+
+    let mut response = db
+        .query("UPDATE $id SET deleted_at = time::now(), version += 1;")
+        .bind(("id", thing(&id)))
+        .await?;
+    let deleted: Option<Investment> = response.take(0)?;
+    let deleted = deleted.ok_or_else(|| not_found(&id))?;
+
+    let changes = FieldChange::diff(Some(&before), &deleted);
+    audit::record(db, actor, AuditAction::Delete, &id, changes).await?;
+
+    Ok(Record { id })
+}
+
+/// Takes the investment `id` out of the trash.
+pub async fn restore_inv(db: &Db, actor: &Actor, id: String) -> Result<Investment> {
+    let id = parse_id(&id)?;
+    let before = trashed_inv(db, &id).await?;
+
+    let mut response = db
+        .query("UPDATE $id SET deleted_at = NONE, updated_at = time::now(), version += 1;")
+        .bind(("id", thing(&id)))
+        .await?;
+    let restored: Option<Investment> = response.take(0)?;
+    let restored = restored.ok_or_else(|| not_found(&id))?;
+
+    let changes = FieldChange::diff(Some(&before), &restored);
+    audit::record(db, actor, AuditAction::Restore, &id, changes).await?;
+
+    Ok(check_return_amount(restored))
+}
+
+/// Deletes the investment `id` for good. Only investments in the trash can be
+/// purged; the `renewed_to` edges go with it, its history stays.
+pub async fn purge_inv(db: &Db, actor: &Actor, id: String) -> Result<Record> {
+    let id = parse_id(&id)?;
+    trashed_inv(db, &id).await?;
+
+    let purged: Option<Record> = db.delete(thing(&id)).await?;
+    let purged = purged.ok_or_else(|| not_found(&id))?;
+
+    audit::record(db, actor, AuditAction::Purge, &id, Vec::new()).await?;
+
+    Ok(purged)
+}
+
+/// Investments in the trash, most recently deleted first.
+pub async fn get_trash(db: &Db) -> Result<Vec<Investment>> {
+    let mut response = db
+        .query(
+            "SELECT * FROM type::table($table) WHERE deleted_at != NONE ORDER BY deleted_at DESC;",
+        )
+        .bind(("table", INVESTMENT))
+        .await?;
+    let invs: Vec<Investment> = response.take(0)?;
+
+    Ok(invs.into_iter().map(check_return_amount).collect())
+}
+
+/// Fields set by the API, which updates can not change.
+const SERVER_MANAGED_FIELDS: [&str; 7] = [
+    "id",
+    "created_at",
+    "updated_at",
+    "version",
+    "deleted_at",
+    "inv_status",
+    "return_mismatch",
+];
+
+/// Applies a JSON merge patch (RFC 7386) to `target`: objects are merged
+/// recursively, `null` removes a field and anything else replaces it.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (field, value) in patch {
+            if value.is_null() {
+                target.remove(field);
+            } else {
+                merge_patch(target.entry(field.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+fn conflict(id: &RecordId) -> Error {
+    Error::Conflict(format!(
+        "investment '{id}' was changed by someone else, reload it and try again"
+    ))
+}
+
+/// The id of a patch that carries it in its body, as the deprecated
+/// `PATCH /inv` takes them.
+pub fn take_patch_id(patch: &mut Map<String, Value>) -> Result<String> {
+    match patch.remove("id").filter(|id| !id.is_null()) {
+        Some(id) => serde_json::from_value::<RecordId>(id)
+            .map(|id| id.to_string())
+            .map_err(|_| Error::BadRequest("the id of the investment is not valid".into())),
+        None => Err(Error::BadRequest(
+            "an id is required to update an investment".into(),
+        )),
+    }
+}
+
+/// The status a patch moves an investment to, given as
+/// `{ "inv_status": { "status": "closed" } }`.
+fn patched_status(patch: &Map<String, Value>) -> Result<Option<InvestmentStatus>> {
+    let Some(status) = patch
+        .get("inv_status")
+        .and_then(|inv_status| inv_status.get("status"))
+        .filter(|status| !status.is_null())
+    else {
+        return Ok(None);
+    };
+    let status = serde_json::from_value(status.clone())
+        .map_err(|_| Error::BadRequest(format!("{status} is not a status of an investment")))?;
+    if status == InvestmentStatus::Renewed {
+        return Err(Error::BadRequest(
+            "investments are renewed with POST /inv/{id}/renew".into(),
+        ));
+    }
+
+    Ok(Some(status))
+}
+
+/// Applies `patch`, a JSON merge patch, to the investment `id` and returns
+/// the updated investment. Its status can only be moved along the lifecycle
+/// of an investment, see [`InvestmentStatus::next`].
+///
+/// When `expected_version` is given, or the patch has a `version`, the update
+/// only goes through if the investment is still at that version.
+pub async fn update_inv(
+    db: &Db,
+    actor: &Actor,
+    id: String,
+    mut patch: Map<String, Value>,
+    expected_version: Option<u64>,
+) -> Result<Investment> {
+    let id = parse_id(&id)?;
+    // the patch may repeat the id, but not name another investment
+    if let Some(patch_id) = patch.remove("id").filter(|id| !id.is_null()) {
+        if serde_json::from_value::<RecordId>(patch_id).ok().as_ref() != Some(&id) {
+            return Err(Error::BadRequest(format!(
+                "the id in the patch does not match '{id}'"
+            )));
+        }
+    }
+    let expected_version =
+        expected_version.or_else(|| patch.get("version").and_then(Value::as_u64));
+    let status = patched_status(&patch)?;
+    for field in SERVER_MANAGED_FIELDS {
+        patch.remove(field);
+    }
+
+    // UPDATE would create a missing record, so check that it exists first
+    let before = live_inv(db, &id).await?;
+    if expected_version.is_some_and(|version| version != before.version) {
+        return Err(conflict(&id));
+    }
+    // a status sent back unchanged, e.g. with the whole investment, is no move
+    if let Some(status) = status.filter(|&status| status != before.status()) {
+        let status = before
+            .transition(status, Utc::now())
+            .map_err(|e| Error::Conflict(e.to_string()))?;
+        let inv_status = InvStatus {
+            id: before.inv_status.as_ref().and_then(|s| s.id.clone()),
+            status,
+        };
+        patch.insert(
+            "inv_status".into(),
+            serde_json::to_value(inv_status).map_err(|e| Error::Internal(e.to_string()))?,
+        );
+    }
 
-    Ok(created.clone().pop().unwrap())
+    // validate the investment as it will be once patched
+    let mut merged = serde_json::to_value(&before).map_err(|e| Error::Internal(e.to_string()))?;
+    merge_patch(&mut merged, &Value::Object(patch.clone()));
+    let merged: Investment = serde_json::from_value(merged)
+        .map_err(|e| Error::BadRequest(format!("the patched investment is not valid: {e}")))?;
+    validation::validate(&merged).map_err(Error::Validation)?;
+
+    patch.insert("version".into(), (before.version + 1).into());
+    patch.insert(
+        "updated_at".into(),
+        serde_json::to_value(Utc::now()).map_err(|e| Error::Internal(e.to_string()))?,
+    );
+
+    // the version is checked again in case of a concurrent update
+    let mut response = db
+        .query("UPDATE $id MERGE $patch WHERE version = $version;")
+        .bind(("id", thing(&id)))
+        .bind(("patch", Value::Object(patch)))
+        .bind(("version", before.version))
+        .await?;
+    let updated: Option<Investment> = response.take(0)?;
+    let updated = updated.ok_or_else(|| conflict(&id))?;
+
+    let changes = FieldChange::diff(Some(&before), &updated);
+    audit::record(db, actor, AuditAction::Update, &id, changes).await?;
+
+    Ok(check_return_amount(updated))
+}
+
+/// Marks the matured investment `id` as renewed and creates `inv` as its
+/// renewal, linked to it by a `renewed_to` edge, in a single transaction so
+/// that the money is never counted twice.
+pub async fn renew_inv(
+    db: &Db,
+    actor: &Actor,
+    id: String,
+    inv: &mut Investment,
+) -> Result<Renewal> {
+    let id = parse_id(&id)?;
+    let old = live_inv(db, &id).await?;
+    old.transition(InvestmentStatus::Renewed, Utc::now())
+        .map_err(|e| Error::Conflict(format!("investment '{id}' can not be renewed: {e}")))?;
+
+    prepare_new(inv);
+    inv.inv_status = Some(InvStatus {
+        id: Some(id.clone()),
+        status: InvestmentStatus::Active,
+    });
+
+    // the status is checked again in the transaction in case of a concurrent renewal
+    let sql = "
+        BEGIN TRANSACTION;
+        LET $previous = (
+            UPDATE $old SET
+                inv_status = { id: inv_status.id, status: $status },
+                updated_at = time::now(),
+                version += 1
+            WHERE (inv_status.status ?? 'active') IN ['active', 'matured']
+        );
+        IF array::len($previous) = 0 {
+            THROW 'the investment has already been renewed or paid out';
+        };
+        LET $renewed = (CREATE type::table($table) CONTENT $inv);
+        LET $next = $renewed[0].id;
+        RELATE $old->renewed_to->$next SET renewed_at = time::now();
+        RETURN { previous: $previous[0], renewed: $renewed[0] };
+        COMMIT TRANSACTION;
+    ";
+
+    let mut response = db
+        .query(sql)
+        .bind(("old", thing(&id)))
+        .bind(("status", InvestmentStatus::Renewed.as_str()))
+        .bind(("table", INVESTMENT))
+        .bind(("inv", &inv))
+        .await?
+        .check()?;
+
+    let last = response.num_statements() - 1;
+    let renewal: Option<Renewal> = response.take(last)?;
+    let renewal =
+        renewal.ok_or_else(|| Error::Internal("the investment was not renewed".into()))?;
+
+    let changes = FieldChange::diff(Some(&old), &renewal.previous);
+    audit::record(db, actor, AuditAction::Renew, &id, changes).await?;
+    if let Some(renewed_id) = &renewal.renewed.id {
+        let changes = FieldChange::diff(None, &renewal.renewed);
+        audit::record(db, actor, AuditAction::Create, renewed_id, changes).await?;
+    }
+
+    Ok(Renewal {
+        previous: check_return_amount(renewal.previous),
+        renewed: check_return_amount(renewal.renewed),
+    })
+}
+
+/// Finds the investment a renewal came from.
+const PREVIOUS_IN_CHAIN: &str = "SELECT VALUE in FROM renewed_to WHERE out = $id;";
+/// Finds the renewal of an investment.
+const NEXT_IN_CHAIN: &str = "SELECT VALUE out FROM renewed_to WHERE in = $id;";
+
+/// The investment linked to `id` by `sql`, one of the queries above.
+async fn linked_inv(db: &Db, id: &RecordId, sql: &str) -> Result<Option<RecordId>> {
+    let mut response = db.query(sql).bind(("id", thing(id))).await?;
+    let ids: Vec<RecordId> = response.take(0)?;
+
+    Ok(ids.into_iter().next())
+}
+
+/// The investment `id` with every investment it was renewed from and into.
+pub async fn get_chain(db: &Db, id: String) -> Result<RenewalChain> {
+    let id = parse_id(&id)?;
+    live_inv(db, &id).await?;
+
+    // walk back to the first investment, then forward to the last one; the
+    // checks for ids already in the chain guard against cycles
+    let mut ids = VecDeque::from([id.clone()]);
+    while let Some(previous) = linked_inv(db, &ids[0], PREVIOUS_IN_CHAIN).await? {
+        if ids.contains(&previous) {
+            break;
+        }
+        ids.push_front(previous);
+    }
+    while let Some(next) = linked_inv(db, &ids[ids.len() - 1], NEXT_IN_CHAIN).await? {
+        if ids.contains(&next) {
+            break;
+        }
+        ids.push_back(next);
+    }
+
+    let things: Vec<Thing> = ids.iter().map(thing).collect();
+    let mut response = db
+        .query("SELECT * FROM $ids;")
+        .bind(("ids", things))
+        .await?;
+    let invs: Vec<Investment> = response.take(0)?;
+
+    Ok(RenewalChain::new(
+        invs.into_iter().map(check_return_amount),
+        Utc::now(),
+    ))
+}
+
+/// Checks a bulk operation before anything is applied, returning the
+/// investment it applies to as it is now.
+async fn check_bulk_operation(
+    db: &Db,
+    operation: &mut BulkOperation,
+    seen: &mut HashSet<RecordId>,
+) -> Result<Option<Investment>> {
+    if let BulkOperation::Create { investment } = operation {
+        validation::validate(investment).map_err(Error::Validation)?;
+        prepare_new(investment);
+        return Ok(None);
+    }
+    if let BulkOperation::AddTags { tags, .. } = operation {
+        *tags = normalize_tags(std::mem::take(tags));
+        if tags.is_empty() {
+            return Err(Error::BadRequest("there are no tags to add".into()));
+        }
+    }
+
+    let Some(id) = operation.id() else {
+        return Ok(None);
+    };
+    check_id(id)?;
+    // the changes of one operation would be hidden from the audit of another
+    if !seen.insert(id.clone()) {
+        return Err(Error::BadRequest(format!(
+            "investment '{id}' is in more than one operation"
+        )));
+    }
+
+    let inv = live_inv(db, id).await?;
+    if let BulkOperation::SetStatus { status, .. } = operation {
+        if *status == InvestmentStatus::Renewed {
+            return Err(Error::BadRequest(
+                "investments are renewed with POST /inv/{id}/renew".into(),
+            ));
+        }
+        inv.transition(*status, Utc::now())
+            .map_err(|e| Error::Conflict(e.to_string()))?;
+    }
+
+    Ok(Some(inv))
+}
+
+/// Message the bulk transaction is aborted with when an investment was
+/// deleted or changed status after its operation was checked.
+const BULK_STALE: &str = "an investment changed while the operations were applied";
+
+/// Stored statuses from which an investment like `inv` can be moved to
+/// `status` as of `now`.
+fn statuses_from(
+    inv: &Investment,
+    status: InvestmentStatus,
+    now: DateTime<Utc>,
+) -> Vec<&'static str> {
+    InvestmentStatus::ALL
+        .into_iter()
+        .filter(|&from| {
+            let inv = Investment {
+                inv_status: Some(InvStatus {
+                    id: None,
+                    status: from,
+                }),
+                ..inv.clone()
+            };
+            inv.transition(status, now).is_ok()
+        })
+        .map(|from| from.as_str())
+        .collect()
+}
+
+/// Applies `operations` in a single transaction, or none of them if any of
+/// them can not be applied.
+pub async fn bulk_invs(
+    db: &Db,
+    actor: &Actor,
+    mut operations: Vec<BulkOperation>,
+) -> Result<BulkReport> {
+    if operations.is_empty() {
+        return Err(Error::BadRequest("there are no operations to apply".into()));
+    }
+    if operations.len() > MAX_BULK_OPERATIONS {
+        return Err(Error::BadRequest(format!(
+            "at most {MAX_BULK_OPERATIONS} operations can be applied at once"
+        )));
+    }
+
+    let mut befores = Vec::with_capacity(operations.len());
+    let mut results = Vec::with_capacity(operations.len());
+    let mut seen = HashSet::new();
+    for (index, operation) in operations.iter_mut().enumerate() {
+        let checked = check_bulk_operation(db, operation, &mut seen).await;
+        results.push(BulkItemResult {
+            index,
+            id: operation.id().cloned(),
+            error: checked.as_ref().err().map(Error::to_api_error),
+        });
+        befores.push(checked.ok().flatten());
+    }
+    if results.iter().any(|result| result.error.is_some()) {
+        return Ok(BulkReport {
+            applied: false,
+            results,
+        });
+    }
+
+    // each operation gets its own parameters, $p<index>, $v<index> and
+    // $f<index>; the checks above are made again in the transaction, so that
+    // nothing is applied if an investment changed in the meantime
+    let mut sql = String::from("BEGIN TRANSACTION;\n");
+    for (index, operation) in operations.iter().enumerate() {
+        let statement = match operation {
+            BulkOperation::Create { .. } => "CREATE type::table($table) CONTENT $p",
+            BulkOperation::Delete { .. } => {
+                "UPDATE $p SET deleted_at = time::now(), version += 1
+                WHERE deleted_at = NONE"
+            }
+            BulkOperation::SetStatus { .. } => {
+                "UPDATE $p SET
+                    inv_status = { id: inv_status.id, status: $v },
+                    updated_at = time::now(),
+                    version += 1
+                WHERE deleted_at = NONE AND (inv_status.status ?? 'active') IN $f"
+            }
+            BulkOperation::AddTags { .. } => {
+                "UPDATE $p SET
+                    tags = array::union(tags ?? [], $v),
+                    updated_at = time::now(),
+                    version += 1
+                WHERE deleted_at = NONE"
+            }
+        };
+        let statement = statement
+            .replace("$p", &format!("$p{index}"))
+            .replace("$v", &format!("$v{index}"))
+            .replace("$f", &format!("$f{index}"));
+        sql.push_str(&format!("LET $r{index} = ({statement});\n"));
+        if operation.id().is_some() {
+            sql.push_str(&format!(
+                "IF array::len($r{index}) = 0 {{ THROW '{BULK_STALE}'; }};\n"
+            ));
+        }
+    }
+    let returned: Vec<String> = (0..operations.len())
+        .map(|index| format!("$r{index}[0]"))
+        .collect();
+    sql.push_str(&format!("RETURN [{}];\n", returned.join(", ")));
+    sql.push_str("COMMIT TRANSACTION;");
+
+    let now = Utc::now();
+    let mut query = db.query(sql).bind(("table", INVESTMENT));
+    for (index, (operation, before)) in operations.iter().zip(&befores).enumerate() {
+        let (p, v, f) = (
+            format!("p{index}"),
+            format!("v{index}"),
+            format!("f{index}"),
+        );
+        query = match operation {
+            BulkOperation::Create { investment } => query.bind((p, investment)),
+            BulkOperation::Delete { id } => query.bind((p, thing(id))),
+            BulkOperation::SetStatus { id, status } => {
+                let from: Vec<&str> = before
+                    .iter()
+                    .flat_map(|before| statuses_from(before, *status, now))
+                    .collect();
+                query
+                    .bind((p, thing(id)))
+                    .bind((v, status.as_str()))
+                    .bind((f, from))
+            }
+            BulkOperation::AddTags { id, tags } => query.bind((p, thing(id))).bind((v, tags)),
+        };
+    }
+    let mut response = query
+        .await
+        .and_then(|response| response.check())
+        .map_err(|e| {
+            if e.to_string().contains(BULK_STALE) {
+                Error::Conflict(BULK_STALE.into())
+            } else {
+                Error::Db(e)
+            }
+        })?;
+
+    let last = response.num_statements() - 1;
+    let afters: Vec<Investment> = response.take(last)?;
+    if afters.len() != operations.len() {
+        return Err(Error::Internal(
+            "the bulk operations were not applied".into(),
+        ));
+    }
+
+    for ((operation, before), after) in operations.iter().zip(&befores).zip(&afters) {
+        let Some(id) = &after.id else { continue };
+        let action = match operation {
+            BulkOperation::Create { .. } => AuditAction::Create,
+            BulkOperation::Delete { .. } => AuditAction::Delete,
+            BulkOperation::SetStatus { .. } | BulkOperation::AddTags { .. } => AuditAction::Update,
+        };
+        let changes = FieldChange::diff(before.as_ref(), after);
+        audit::record(db, actor, action, id, changes).await?;
+    }
+
+    Ok(BulkReport {
+        applied: true,
+        results: afters
+            .into_iter()
+            .enumerate()
+            .map(|(index, after)| BulkItemResult {
+                index,
+                id: after.id,
+                error: None,
+            })
+            .collect(),
+    })
+}
+
+/// Imports the investments in the file of `request`, all of them or, if any
+/// row has errors or it is a dry run, none.
+pub async fn import_invs(db: &Db, actor: &Actor, request: &ImportRequest) -> Result<ImportReport> {
+    let mut rows = import::parse(request)?;
+    let mut report = ImportReport {
+        dry_run: request.dry_run,
+        imported: 0,
+        rows: Vec::new(),
+    };
+    if request.dry_run || rows.iter().any(|row| !row.errors.is_empty()) {
+        report.rows = rows;
+        return Ok(report);
+    }
+
+    let operations = rows
+        .iter()
+        .map(|row| BulkOperation::Create {
+            investment: Box::new(row.investment.clone()),
+        })
+        .collect();
+    let bulk = bulk_invs(db, actor, operations).await?;
+    for (row, result) in rows.iter_mut().zip(bulk.results) {
+        if let Some(error) = result.error {
+            row.errors.insert("row".into(), error.message);
+        }
+        row.investment.id = result.id;
+    }
+    if bulk.applied {
+        report.imported = rows.len();
+    }
+    report.rows = rows;
+
+    Ok(report)
 }
 
-pub async fn get_inv(id: String) -> Result<Investment> {
-    let th = id.split_once(':').unwrap();
-    let rec: Option<Investment> = DB.select(th).await?;
+/// Changes made to the investment `id`, oldest first. The history outlives
+/// the investment, so purged investments still have one.
+pub async fn get_history(db: &Db, id: String) -> Result<Vec<AuditEntry>> {
+    let id = parse_id(&id)?;
+
+    let mut response = db
+        .query("SELECT * FROM type::table($table) WHERE investment = $id ORDER BY at ASC;")
+        .bind(("table", AUDIT))
+        .bind(("id", id.to_string()))
+        .await?;
+    let entries: Vec<AuditEntry> = response.take(0)?;
 
-    Ok(rec.unwrap())
+    if entries.is_empty() {
+        // investments from before the audit log have no history yet
+        let existing: Option<Record> = db.select(thing(&id)).await?;
+        if existing.is_none() {
+            return Err(not_found(&id));
+        }
+    }
+
+    Ok(entries)
 }
 
-pub async fn delete_inv(id: Thing) -> Result<Record> {
-    let response_option: Option<Record> = DB.delete(id).await?;
-    let response = response_option.ok_or(Error::Generic("Failed to delete record".into()))?;
+/// Start of the given day, to compare against the stored dates.
+fn day_start(date: NaiveDate) -> Option<DateTime<Utc>> {
+    date.and_hms_opt(0, 0, 0)
+        .map(|datetime| Utc.from_utc_datetime(&datetime))
+}
+
+pub async fn get_all_invs(db: &Db, query: &ListQuery) -> Result<Page<Investment>> {
+    let (invs, total) = select_invs(db, query, true).await?;
+
+    Ok(Page {
+        items: invs,
+        total,
+        page: query.page(),
+        per_page: query.per_page(),
+    })
+}
 
-    Ok(response)
+/// Every investment matching the filters of `query`, in its order.
+pub async fn export_invs(db: &Db, query: &ListQuery) -> Result<Vec<Investment>> {
+    let (invs, _) = select_invs(db, query, false).await?;
+    Ok(invs)
 }
 
-pub async fn update_inv(inv: &mut Investment) -> Result<Investment> {
-    let thing = match inv.id.clone() {
-        Some(thing) => thing,
-        None => return Err(Error::Generic("Failed to update record".into())),
+/// Investments matching the filters of `query` in its order, only those of
+/// its page if `paged`, along with how many match in total.
+async fn select_invs(db: &Db, query: &ListQuery, paged: bool) -> Result<(Vec<Investment>, u64)> {
+    // investments in the trash are only listed by `get_trash`
+    let mut conditions = vec!["deleted_at = NONE"];
+    if query.inv_type.is_some() {
+        conditions.push("inv_type = $inv_type");
+    }
+    if query.return_type.is_some() {
+        conditions.push("return_type = $return_type");
+    }
+    if query.status.is_some() {
+        // investments that were never renewed have no status and are active
+        conditions.push("(inv_status.status ?? 'active') = $status");
+    }
+    if query.name.is_some() {
+        conditions.push("string::lowercase(name) CONTAINS string::lowercase($name)");
+    }
+    // rows saved before the dates were required may have none, which can not
+    // be cast to a datetime
+    if query.start_from.is_some() {
+        conditions.push("start_date != NONE AND <datetime> start_date >= <datetime> $start_from");
+    }
+    if query.start_to.is_some() {
+        conditions.push("start_date != NONE AND <datetime> start_date < <datetime> $start_to");
+    }
+    if query.end_from.is_some() {
+        conditions.push("end_date != NONE AND <datetime> end_date >= <datetime> $end_from");
+    }
+    if query.end_to.is_some() {
+        conditions.push("end_date != NONE AND <datetime> end_date < <datetime> $end_to");
+    }
+    let filter = format!("WHERE {}", conditions.join(" AND "));
+
+    // the sort column comes from a fixed list, so it is safe to splice in;
+    // the id breaks ties so that pages neither repeat nor skip rows
+    let limit = if paged {
+        "LIMIT $limit START $start"
+    } else {
+        ""
     };
-    let response_option: Option<Investment> = DB.update(thing).content(inv).await?;
-    let response = response_option.ok_or(Error::Generic("Failed to update record".into()))?;
+    let sql = format!(
+        "SELECT * FROM type::table($table) {filter} ORDER BY {sort} {order}, id {order} {limit};
+        SELECT count() AS total FROM type::table($table) {filter} GROUP ALL;",
+        sort = query.sort().column(),
+        order = query.order(),
+    );
+
+    let mut response = db
+        .query(sql)
+        .bind(("table", INVESTMENT))
+        .bind(("inv_type", query.inv_type.map(|t| t.as_str())))
+        .bind(("return_type", query.return_type.map(|t| t.as_str())))
+        .bind(("status", query.status.map(|s| s.as_str())))
+        .bind(("name", query.name.clone()))
+        .bind(("start_from", query.start_from.and_then(day_start)))
+        // the "to" dates are inclusive, so compare against the start of the next day
+        .bind((
+            "start_to",
+            query
+                .start_to
+                .and_then(|d| d.succ_opt())
+                .and_then(day_start),
+        ))
+        .bind(("end_from", query.end_from.and_then(day_start)))
+        .bind((
+            "end_to",
+            query.end_to.and_then(|d| d.succ_opt()).and_then(day_start),
+        ))
+        .bind(("limit", query.per_page()))
+        .bind(("start", query.offset()))
+        .await?;
 
-    Ok(response)
+    let invs: Vec<Investment> = response.take(0)?;
+    let total: Option<u64> = response.take((1, "total"))?;
+
+    Ok((
+        invs.into_iter().map(check_return_amount).collect(),
+        total.unwrap_or(0),
+    ))
 }
 
-pub async fn get_all_invs() -> Result<Vec<Investment>> {
-    // let tasks: Vec<Task> = DB.select(TASK).await?;
+/// Totals of one status as returned by the summary query. Investments that
+/// were never renewed have no status and count as active.
+#[derive(Deserialize)]
+struct StatusTotalsRow {
+    status: Option<InvestmentStatus>,
+    count: u64,
+    invested: Money,
+    expected_return: Money,
+    interest_earned: Money,
+}
 
-    // Ok(tasks)
-    let sql = "SELECT * FROM type::table($table) ORDER BY created_at DESC;";
+pub async fn get_summary(db: &Db) -> Result<PortfolioSummary> {
+    let sql = "
+        SELECT
+            inv_status.status AS status,
+            count() AS count,
+            math::sum(inv_amount) AS invested,
+            math::sum(return_amount) AS expected_return,
+            math::sum(IF end_date != NONE AND <datetime> end_date <= time::now() THEN return_amount - inv_amount ELSE 0 END) AS interest_earned
+        FROM type::table($table)
+        WHERE deleted_at = NONE
+        GROUP BY status;
+    ";
 
-    let mut response = DB.query(sql).bind(("table", INVESTMENT)).await?;
+    let mut response = db.query(sql).bind(("table", INVESTMENT)).await?;
+
+    let rows: Vec<StatusTotalsRow> = response.take(0)?;
+
+    Ok(PortfolioSummary::from_totals(rows.into_iter().map(|row| {
+        StatusTotals {
+            status: row.status.unwrap_or_default(),
+            count: row.count,
+            invested: row.invested,
+            expected_return: row.expected_return,
+            interest_earned: row.interest_earned,
+        }
+    })))
+}
 
-    let tasks: Vec<Investment> = response.take(0)?;
+/// Analytics of the live investments, see [`PortfolioAnalytics`].
+pub async fn get_analytics(db: &Db) -> Result<PortfolioAnalytics> {
+    let mut response = db
+        .query("SELECT * FROM type::table($table) WHERE deleted_at = NONE;")
+        .bind(("table", INVESTMENT))
+        .await?;
+    let invs: Vec<Investment> = response.take(0)?;
 
-    Ok(tasks)
+    Ok(PortfolioAnalytics::new(&invs, Utc::now()))
 }
 
 /*
@@ -1,28 +1,90 @@
-use actix_web::{HttpResponse, ResponseError};
+use actix_web::error::{JsonPayloadError, PathError, QueryPayloadError};
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, HttpResponse, ResponseError};
 use thiserror::Error;
 
+use types::{ApiError, FieldErrors};
+
 #[derive(Error, Debug)]
 pub enum Error {
-    /// For starter, to remove as code matures.
-    #[error("Generic error: {0}")]
-    Generic(String),
+    #[error("{0}")]
+    NotFound(String),
+
+    /// The payload is well formed but breaks the rules of an investment.
+    #[error("the investment is not valid")]
+    Validation(FieldErrors),
+
+    /// The request clashes with the current state of the record.
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    BadRequest(String),
 
     #[error("database error")]
-    Db,
+    Db(#[from] surrealdb::Error),
+
+    /// Something that should not happen did, e.g. the database returned no
+    /// record for a successful create.
+    #[error("{0}")]
+    Internal(String),
 }
 
-impl ResponseError for Error {
-    fn error_response(&self) -> HttpResponse {
+impl Error {
+    /// Machine readable kind of the error, sent as `error` in the response body.
+    fn kind(&self) -> &'static str {
         match self {
-            Error::Db => HttpResponse::InternalServerError().body(self.to_string()),
-            Error::Generic(msg) => HttpResponse::InternalServerError().body(msg.clone()),
+            Error::NotFound(_) => "not_found",
+            Error::Validation(_) => "validation",
+            Error::Conflict(_) => "conflict",
+            Error::BadRequest(_) => "bad_request",
+            Error::Db(_) => "database",
+            Error::Internal(_) => "internal",
+        }
+    }
+
+    /// Body sent to the client for this error.
+    pub fn to_api_error(&self) -> ApiError {
+        let mut body = ApiError::new(self.status_code().as_u16(), self.kind(), self.to_string());
+        if let Error::Validation(fields) = self {
+            body.fields = fields.clone();
         }
+        body
     }
 }
 
-impl From<surrealdb::Error> for Error {
-    fn from(error: surrealdb::Error) -> Self {
-        eprintln!("{error}");
-        Self::Db
+impl ResponseError for Error {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            Error::NotFound(_) => StatusCode::NOT_FOUND,
+            Error::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::Conflict(_) => StatusCode::CONFLICT,
+            Error::BadRequest(_) => StatusCode::BAD_REQUEST,
+            Error::Db(_) | Error::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        // the cause of a database error is logged but not sent to the client
+        if let Error::Db(source) = self {
+            log::error!("{source}");
+        }
+
+        HttpResponse::build(self.status_code()).json(self.to_api_error())
     }
 }
+
+/// Turns a malformed JSON body into a 400 with the same body as other errors.
+pub fn json_error(error: JsonPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    Error::BadRequest(error.to_string()).into()
+}
+
+/// Turns malformed query parameters into a 400 with the same body as other errors.
+pub fn query_error(error: QueryPayloadError, _req: &HttpRequest) -> actix_web::Error {
+    Error::BadRequest(error.to_string()).into()
+}
+
+/// Turns a malformed path into a 400 with the same body as other errors.
+pub fn path_error(error: PathError, _req: &HttpRequest) -> actix_web::Error {
+    Error::BadRequest(error.to_string()).into()
+}
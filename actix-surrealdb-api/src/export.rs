@@ -0,0 +1,143 @@
+//! Writing investments out as the files of `GET /invs/export`.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+use crate::prelude::*;
+use types::*;
+
+/// Headers of the exported columns, in the order of the table of the web app.
+pub const EXPORT_COLUMNS: [&str; 10] = [
+    "Start Date",
+    "End Date",
+    "Investment Name",
+    "Name",
+    "Investment Type",
+    "Return Type",
+    "Return Rate",
+    "Investment",
+    "Return",
+    "Tags",
+];
+
+/// Date format of the exported files, the one used to show dates and read
+/// imported files.
+const DATE_FORMAT: &str = "%d-%m-%Y";
+
+/// First characters that make spreadsheets read a CSV cell as a formula.
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+enum Cell {
+    Text(String),
+    Date(Option<NaiveDate>),
+    Money(Money),
+    Rate(Rate),
+}
+
+fn cells(inv: &Investment) -> [Cell; EXPORT_COLUMNS.len()] {
+    let date = |date: Option<DateTime<Utc>>| Cell::Date(date.map(|date| date.date_naive()));
+
+    [
+        date(inv.start_date),
+        date(inv.end_date),
+        Cell::Text(inv.inv_name.clone()),
+        Cell::Text(inv.name.clone()),
+        Cell::Text(inv.inv_type.to_string()),
+        Cell::Text(inv.return_type.to_string()),
+        Cell::Rate(inv.return_rate),
+        Cell::Money(inv.inv_amount),
+        Cell::Money(inv.return_amount),
+        // the separator imports read tags with
+        Cell::Text(inv.tags.join(";")),
+    ]
+}
+
+/// Writes `invs` in `format`.
+pub fn write(format: ExportFormat, invs: &[Investment]) -> Result<Vec<u8>> {
+    match format {
+        ExportFormat::Csv => to_csv(invs),
+        ExportFormat::Json => serde_json::to_vec(invs).map_err(|e| Error::Internal(e.to_string())),
+        ExportFormat::Xlsx => to_xlsx(invs).map_err(|e| Error::Internal(e.to_string())),
+    }
+}
+
+fn to_csv(invs: &[Investment]) -> Result<Vec<u8>> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut write = |record: Vec<String>| {
+        writer
+            .write_record(record)
+            .map_err(|e| Error::Internal(e.to_string()))
+    };
+
+    write(EXPORT_COLUMNS.map(str::to_string).to_vec())?;
+    for inv in invs {
+        let record = cells(inv).map(|cell| match cell {
+            Cell::Text(text) => escape_formula(text),
+            Cell::Date(date) => date
+                .map(|date| date.format(DATE_FORMAT).to_string())
+                .unwrap_or_default(),
+            Cell::Money(money) => money.to_string(),
+            Cell::Rate(rate) => rate.to_string(),
+        });
+        write(record.to_vec())?;
+    }
+
+    writer
+        .into_inner()
+        .map_err(|e| Error::Internal(e.to_string()))
+}
+
+/// Quotes `text` with a `'` if a spreadsheet would run it as a formula, so
+/// that a name like `=HYPERLINK(...)` is shown as it was typed instead.
+fn escape_formula(text: String) -> String {
+    if text.starts_with(FORMULA_PREFIXES) {
+        format!("'{text}")
+    } else {
+        text
+    }
+}
+
+fn to_xlsx(invs: &[Investment]) -> std::result::Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Investments")?;
+
+    let bold = Format::new().set_bold();
+    let date_format = Format::new().set_num_format("dd-mm-yyyy");
+    let money_format = Format::new().set_num_format("#,##0.00");
+    let rate_format = Format::new().set_num_format("0.00\"%\"");
+
+    for (col, header) in (0..).zip(EXPORT_COLUMNS) {
+        sheet.write_string_with_format(0, col, header, &bold)?;
+        sheet.set_column_width(col, 16)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    for (row, inv) in (1..).zip(invs) {
+        for (col, cell) in (0..).zip(cells(inv)) {
+            match cell {
+                Cell::Text(text) => sheet.write_string(row, col, text)?,
+                Cell::Date(Some(date)) => {
+                    sheet.write_datetime_with_format(row, col, date, &date_format)?
+                }
+                Cell::Date(None) => sheet,
+                Cell::Money(money) => {
+                    sheet.write_number_with_format(row, col, money_major(money), &money_format)?
+                }
+                Cell::Rate(rate) => sheet.write_number_with_format(
+                    row,
+                    col,
+                    f64::from(rate.bps()) / 100.0,
+                    &rate_format,
+                )?,
+            };
+        }
+    }
+
+    workbook.save_to_buffer()
+}
+
+/// The amount in rupees, as spreadsheets want numbers.
+fn money_major(money: Money) -> f64 {
+    money.minor() as f64 / 100.0
+}
@@ -0,0 +1,241 @@
+//! Reading investments out of the CSV files of `POST /invs/import`.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use csv::{ReaderBuilder, StringRecord, Trim};
+
+use crate::prelude::*;
+use types::*;
+
+/// Reads every row of the file in `request`, with what is wrong with each of
+/// them. Only a file that can not be read at all, e.g. one missing a column,
+/// is an error.
+pub fn parse(request: &ImportRequest) -> Result<Vec<ImportRow>> {
+    for field in request.mapping.keys() {
+        if !IMPORT_FIELDS.iter().any(|(name, _)| name == field) {
+            return Err(Error::BadRequest(format!(
+                "'{field}' is not a field that can be imported"
+            )));
+        }
+    }
+
+    let mut reader = ReaderBuilder::new()
+        .trim(Trim::All)
+        .from_reader(request.csv.as_bytes());
+    let headers = reader
+        .headers()
+        .map_err(|e| Error::BadRequest(format!("the file can not be read: {e}")))?
+        .clone();
+
+    let mut columns = HashMap::new();
+    let mut missing = Vec::new();
+    for (field, required) in IMPORT_FIELDS {
+        let column = request.column(field);
+        match headers.iter().position(|header| header == column) {
+            Some(index) => {
+                columns.insert(field, index);
+            }
+            None if required => missing.push(column),
+            None => {}
+        }
+    }
+    if !missing.is_empty() {
+        return Err(Error::BadRequest(format!(
+            "the file has no column for {}",
+            missing.join(", ")
+        )));
+    }
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        if rows.len() == MAX_BULK_OPERATIONS {
+            return Err(Error::BadRequest(format!(
+                "at most {MAX_BULK_OPERATIONS} investments can be imported at once"
+            )));
+        }
+        let row = match record {
+            Ok(record) => parse_row(request, &columns, &record),
+            Err(e) => {
+                let line = e.position().map_or(0, |position| position.line());
+                let mut errors = FieldErrors::new();
+                errors.insert("row".into(), format!("the row can not be read: {e}"));
+                ImportRow {
+                    line,
+                    investment: Investment::default(),
+                    errors,
+                }
+            }
+        };
+        rows.push(row);
+    }
+    if rows.is_empty() {
+        return Err(Error::BadRequest("the file has no rows".into()));
+    }
+
+    Ok(rows)
+}
+
+fn parse_row(
+    request: &ImportRequest,
+    columns: &HashMap<&str, usize>,
+    record: &StringRecord,
+) -> ImportRow {
+    let mut errors = FieldErrors::new();
+    let value = |field: &str| {
+        columns
+            .get(field)
+            .and_then(|&index| record.get(index))
+            .unwrap_or_default()
+    };
+    let inv_type = read(
+        &mut errors,
+        "inv_type",
+        parse_choice(
+            value("inv_type"),
+            &InvestmentType::ALL,
+            InvestmentType::as_str,
+        ),
+    );
+    let return_type = read(
+        &mut errors,
+        "return_type",
+        parse_choice(value("return_type"), &ReturnType::ALL, ReturnType::as_str),
+    );
+    // blank amounts, rates and dates are left for the validation to report
+    let compounding = read(
+        &mut errors,
+        "compounding",
+        optional(value("compounding"), |value| {
+            parse_choice(value, &Compounding::ALL, Compounding::as_str)
+        }),
+    );
+    let inv_amount = read(
+        &mut errors,
+        "inv_amount",
+        optional(value("inv_amount"), |value| parse_number(value, "₹")),
+    );
+    let return_amount = read(
+        &mut errors,
+        "return_amount",
+        optional(value("return_amount"), |value| parse_number(value, "₹")),
+    );
+    let return_rate = read(
+        &mut errors,
+        "return_rate",
+        optional(value("return_rate"), |value| parse_number(value, "%")),
+    );
+    let start_date = read(
+        &mut errors,
+        "start_date",
+        optional(value("start_date"), |value| {
+            parse_date(value, &request.date_format)
+        }),
+    );
+    let end_date = read(
+        &mut errors,
+        "end_date",
+        optional(value("end_date"), |value| {
+            parse_date(value, &request.date_format)
+        }),
+    );
+
+    let mut inv = Investment {
+        inv_name: value("inv_name").to_string(),
+        name: value("name").to_string(),
+        institution: value("institution").trim().to_string(),
+        inv_type: inv_type.unwrap_or_default(),
+        return_type: return_type.unwrap_or_default(),
+        compounding: compounding.flatten(),
+        inv_amount: inv_amount.flatten().unwrap_or_default(),
+        return_rate: return_rate.flatten().unwrap_or_default(),
+        start_date: start_date.flatten(),
+        end_date: end_date.flatten(),
+        tags: normalize_tags(value("tags").split(';').map(str::to_string)),
+        ..Default::default()
+    };
+    // a blank return amount is the one the investment should return
+    inv.return_amount = match return_amount {
+        Some(Some(amount)) => amount,
+        Some(None) => inv
+            .maturity()
+            .map_or(Money::ZERO, |maturity| maturity.maturity_value),
+        None => Money::ZERO,
+    };
+
+    // the rules are only worth checking for the fields that could be read
+    if let Err(invalid) = validation::validate(&inv) {
+        for (field, message) in invalid {
+            errors.entry(field).or_insert(message);
+        }
+    }
+
+    ImportRow {
+        line: record.position().map_or(0, |position| position.line()),
+        investment: inv,
+        errors,
+    }
+}
+
+/// The value of `field`, or `None` after recording why it could not be read.
+fn read<T>(
+    errors: &mut FieldErrors,
+    field: &str,
+    parsed: std::result::Result<T, String>,
+) -> Option<T> {
+    parsed
+        .map_err(|message| errors.insert(field.replace('_', "-"), message))
+        .ok()
+}
+
+/// Parses `value` unless it is blank.
+fn optional<T>(
+    value: &str,
+    parse: impl FnOnce(&str) -> std::result::Result<T, String>,
+) -> std::result::Result<Option<T>, String> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        parse(value).map(Some)
+    }
+}
+
+/// The variant of `choices` named `value`, ignoring case.
+fn parse_choice<T: Copy>(
+    value: &str,
+    choices: &[T],
+    name: fn(&T) -> &'static str,
+) -> std::result::Result<T, String> {
+    choices
+        .iter()
+        .find(|choice| name(choice).eq_ignore_ascii_case(value))
+        .copied()
+        .ok_or_else(|| {
+            let names: Vec<&str> = choices.iter().map(name).collect();
+            format!("'{value}' is not one of {}", names.join(", "))
+        })
+}
+
+/// Parses an amount or a rate, which spreadsheets often write with its unit.
+fn parse_number<T>(value: &str, unit: &str) -> std::result::Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let number: String = value
+        .replace(unit, "")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    number.parse().map_err(|e: T::Err| e.to_string())
+}
+
+fn parse_date(value: &str, format: &str) -> std::result::Result<DateTime<Utc>, String> {
+    NaiveDate::parse_from_str(value, format)
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| Utc.from_utc_datetime(&date))
+        .ok_or_else(|| format!("'{value}' is not a date like {format}"))
+}
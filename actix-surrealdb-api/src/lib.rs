@@ -0,0 +1,78 @@
+//! Investment tracking API built with Actix-web and SurrealDB.
+//!
+//! The binary in `main.rs` wires this up with its configuration; the tests
+//! build the same [`App`](actix_web::App) around an in-memory database.
+
+pub mod api;
+pub mod audit;
+pub mod calendar;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod export;
+pub mod import;
+pub mod maturity;
+pub mod migrations;
+mod prelude;
+pub mod reminders;
+
+use actix_web::web;
+use surrealdb::engine::any::{self, Any};
+use surrealdb::opt::auth::Root;
+use surrealdb::Surreal;
+
+use api::*;
+use config::DatabaseConfig;
+
+/// Connection to the database, given to the handlers as `web::Data<Db>`.
+pub type Db = Surreal<Any>;
+
+/// Connects to the configured database and applies any pending migrations.
+pub async fn connect(config: &DatabaseConfig) -> Result<Db, error::Error> {
+    let db = any::connect(config.address()).await?;
+
+    // embedded engines run in this process and have no users to sign in as
+    if config.is_remote() {
+        db.signin(Root {
+            username: &config.username,
+            password: &config.password,
+        })
+        .await?;
+    }
+
+    db.use_ns(&config.namespace)
+        .use_db(&config.database)
+        .await?;
+
+    migrations::run(&db).await?;
+
+    Ok(db)
+}
+
+/// Registers the services of the API and the handlers that turn malformed
+/// requests into error responses.
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.app_data(web::JsonConfig::default().error_handler(error::json_error))
+        .app_data(web::QueryConfig::default().error_handler(error::query_error))
+        .app_data(web::PathConfig::default().error_handler(error::path_error))
+        .service(create)
+        .service(get)
+        .service(update)
+        .service(delete)
+        .service(legacy_update)
+        .service(legacy_delete)
+        .service(restore)
+        .service(purge)
+        .service(renew)
+        .service(chain)
+        .service(history)
+        .service(list)
+        .service(export)
+        .service(calendar)
+        .service(trash)
+        .service(bulk)
+        .service(import)
+        .service(summary)
+        .service(analytics)
+        .service(reminders);
+}
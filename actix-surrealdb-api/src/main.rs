@@ -1,56 +1,65 @@
-mod api;
-mod db;
-mod error;
-mod prelude;
-
 use actix_cors::Cors;
 use actix_web::middleware::Logger;
-use actix_web::{App, HttpServer};
-use once_cell::sync::Lazy;
-use surrealdb::engine::remote::ws::{Client, Ws};
-use surrealdb::opt::auth::Root;
-use surrealdb::Surreal;
-
-use api::*;
+use actix_web::{web, App, HttpServer};
 
-static DB: Lazy<Surreal<Client>> = Lazy::new(Surreal::init);
-
-const PORT: u16 = 8080;
+use actix_surrealdb_api::config::Config;
+use actix_surrealdb_api::{maturity, reminders};
 
 #[actix_web::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
-    DB.connect::<Ws>("localhost:8000").await?;
+    let config = Config::load()?;
 
-    DB.signin(Root {
-        username: "root",
-        password: "root",
-    })
-    .await?;
+    env_logger::Builder::new()
+        .parse_filters(&config.log_level)
+        .init();
+
+    log::info!("Configuration:\n{config}");
 
-    DB.use_ns("namespace").use_db("database").await?;
+    let db = actix_surrealdb_api::connect(&config.database).await?;
+    if config.database.is_in_memory() {
+        log::warn!("The database is in memory, everything will be lost when the server stops");
+    }
 
     log::info!("✅ Database connected successfully!!");
 
-    log::info!("✅ Server running at http://localhost:{PORT}");
+    if config.maturity.enabled {
+        maturity::spawn(db.clone(), config.maturity.clone());
+    }
+    if config.reminders.enabled {
+        let notifier = reminders::notifier(&config.reminders)
+            .map_err(|e| format!("could not set up the reminders: {e}"))?;
+        reminders::spawn(db.clone(), config.reminders.clone(), notifier);
+    }
+
+    let server = config.server;
+    log::info!(
+        "✅ Server running at http://{}:{}",
+        server.host,
+        server.port
+    );
 
-    HttpServer::new(|| {
+    let db = web::Data::new(db);
+    let bind = (server.host.clone(), server.port);
+    HttpServer::new(move || {
         let cors = Cors::default()
-            .allow_any_origin()
             .allowed_methods(vec!["GET", "POST", "PATCH", "DELETE"])
-            .allow_any_header()
-            .send_wildcard();
+            .allow_any_header();
+        let cors = if server.allows_any_origin() {
+            cors.allow_any_origin().send_wildcard()
+        } else {
+            server
+                .cors_origins
+                .iter()
+                .fold(cors, |cors, origin| cors.allowed_origin(origin))
+        };
 
         App::new()
             .wrap(cors)
             .wrap(Logger::default())
-            .service(create)
-            .service(get)
-            .service(update)
-            .service(delete)
-            .service(list)
+            .app_data(db.clone())
+            .configure(actix_surrealdb_api::configure)
     })
-    .bind(("localhost", PORT))?
+    .bind(bind)?
     .run()
     .await?;
 
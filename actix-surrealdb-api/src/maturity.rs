@@ -0,0 +1,83 @@
+//! Marks active investments past their end date as matured, so that they
+//! wait to be renewed, closed or withdrawn.
+
+use std::time::Duration;
+
+use actix_web::rt;
+use chrono::{DateTime, Utc};
+
+use crate::audit::AUDIT;
+use crate::config::MaturityConfig;
+use crate::prelude::*;
+use crate::Db;
+use types::*;
+
+/// Who the changes of the maturity task are recorded as made by.
+const SYSTEM: &str = "system";
+
+/// Runs [`mature_overdue`] every `interval_secs` for as long as the server runs.
+pub fn spawn(db: Db, config: MaturityConfig) {
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            match mature_overdue(&db, Utc::now()).await {
+                Ok(matured) if !matured.is_empty() => {
+                    log::info!("Marked {} investments as matured", matured.len())
+                }
+                Ok(_) => {}
+                Err(e) => log::error!("Could not look for matured investments: {e}"),
+            }
+        }
+    });
+}
+
+/// Marks every active investment that ended by `now` as matured, returning
+/// the investments as they are now. Each one is audited in the same
+/// transaction, from the records as they were before and after the update.
+pub async fn mature_overdue(db: &Db, now: DateTime<Utc>) -> Result<Vec<Investment>> {
+    // the audited statuses are spelled out so that they read like the ones
+    // recorded from Rust, with an `id` of `null` when there is none
+    let mut response = db
+        .query(
+            "BEGIN TRANSACTION;
+            LET $due = (SELECT * FROM investment
+                WHERE deleted_at = NONE
+                    AND (inv_status.status ?? 'active') = 'active'
+                    AND end_date != NONE
+                    AND <datetime> end_date <= <datetime> $now);
+            FOR $before IN $due {
+                LET $after = (UPDATE $before.id SET
+                    inv_status = { id: inv_status.id, status: $status },
+                    updated_at = $now,
+                    version += 1
+                RETURN AFTER)[0];
+                CREATE type::table($audit) CONTENT {
+                    investment: <string> $before.id,
+                    action: $action,
+                    actor: $actor,
+                    changes: [{
+                        field: 'inv_status',
+                        from: IF $before.inv_status THEN {
+                            id: $before.inv_status.id ?? NULL,
+                            status: $before.inv_status.status
+                        } ELSE NULL END,
+                        to: { id: $after.inv_status.id ?? NULL, status: $after.inv_status.status }
+                    }],
+                    at: $now
+                };
+            };
+            RETURN (SELECT * FROM $due.id);
+            COMMIT TRANSACTION;",
+        )
+        .bind(("status", InvestmentStatus::Matured.as_str()))
+        .bind(("now", now))
+        .bind(("audit", AUDIT))
+        .bind(("action", AuditAction::Update.as_str()))
+        .bind(("actor", SYSTEM))
+        .await?
+        .check()?;
+
+    let last = response.num_statements() - 1;
+    Ok(response.take(last)?)
+}
@@ -0,0 +1,78 @@
+use crate::prelude::*;
+use crate::Db;
+use types::Record;
+
+const MIGRATION: &str = "migration";
+
+/// Data migrations, applied in order on startup. Each one runs at most once;
+/// a `migration:<name>` record is created in the same transaction to mark it
+/// as applied.
+const MIGRATIONS: &[(&str, &str)] = &[
+    // Amounts used to be whole rupees and rates whole percents; they are now
+    // stored in paise and basis points.
+    (
+        "money_minor_units",
+        "UPDATE investment SET
+            inv_amount = inv_amount * 100,
+            return_amount = return_amount * 100,
+            return_rate = return_rate * 100;",
+    ),
+    // Renewals used to be linked only by the id copied into the status of
+    // the renewed investment; they are now `renewed_to` edges from the old
+    // investment to the new one.
+    (
+        "renewed_to_edges",
+        "DEFINE INDEX renewed_to_unique ON TABLE renewed_to COLUMNS in, out UNIQUE;
+        FOR $inv IN (
+            SELECT id, created_at, inv_status.id AS previous FROM investment
+            WHERE inv_status.status = 'renewed' AND inv_status.id != NONE
+        ) {
+            LET $previous = IF type::is::record($inv.previous) THEN
+                $inv.previous
+            ELSE
+                type::thing('investment', string::split($inv.previous, ':')[1])
+            END;
+            LET $next = $inv.id;
+            RELATE $previous->renewed_to->$next SET renewed_at = $inv.created_at;
+        };",
+    ),
+    // Investments now carry a version, bumped on every change, to detect
+    // concurrent updates.
+    (
+        "investment_versions",
+        "UPDATE investment SET version = 1 WHERE version = NONE;",
+    ),
+    // Investments created by a renewal used to be `renewed` and the ones they
+    // replaced `closed`. Renewed now means the latter, and the new ones are
+    // active like any other; overdue investments are then marked matured by
+    // the maturity job.
+    (
+        "status_lifecycle",
+        "UPDATE investment SET inv_status.status = 'active'
+            WHERE inv_status.status = 'renewed';
+        UPDATE investment SET inv_status.status = 'renewed'
+            WHERE inv_status.status = 'closed' AND array::len(->renewed_to) > 0;",
+    ),
+];
+
+pub async fn run(db: &Db) -> Result<()> {
+    for (name, sql) in MIGRATIONS {
+        let applied: Option<Record> = db.select((MIGRATION, *name)).await?;
+        if applied.is_some() {
+            continue;
+        }
+
+        db.query("BEGIN TRANSACTION;")
+            .query(*sql)
+            .query("CREATE type::thing($table, $name) SET applied_at = time::now();")
+            .query("COMMIT TRANSACTION;")
+            .bind(("table", MIGRATION))
+            .bind(("name", *name))
+            .await?
+            .check()?;
+
+        log::info!("✅ Applied migration {name}");
+    }
+
+    Ok(())
+}
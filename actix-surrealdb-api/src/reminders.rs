@@ -0,0 +1,258 @@
+//! Reminders of investments about to mature, kept in the `reminder` table.
+//!
+//! A background task looks for investments entering one of the configured
+//! windows ahead of their end date, records a reminder for each of them and
+//! delivers it through a [`Notifier`]. Reminders have deterministic ids, so
+//! each one is recorded, and delivered, once.
+
+use std::time::Duration;
+
+use actix_web::rt;
+use async_trait::async_trait;
+use chrono::{DateTime, Days, Utc};
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use surrealdb::sql::Thing;
+
+use crate::config::{EmailConfig, NotifierKind, RemindersConfig};
+use crate::prelude::*;
+use crate::Db;
+use types::*;
+
+pub(crate) const REMINDER: &str = "reminder";
+
+/// Why a reminder could not be delivered.
+pub type NotifyError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Delivers reminders somewhere people will see them.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, reminder: &Reminder) -> std::result::Result<(), NotifyError>;
+}
+
+/// Writes reminders to the server log.
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, reminder: &Reminder) -> std::result::Result<(), NotifyError> {
+        log::info!("⏰ {}", reminder.message(Utc::now()));
+        Ok(())
+    }
+}
+
+/// POSTs reminders as JSON, with a `message` ready to show, to a URL.
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: impl Into<String>) -> Self {
+        WebhookNotifier {
+            client: reqwest::Client::new(),
+            url: url.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, reminder: &Reminder) -> std::result::Result<(), NotifyError> {
+        let body = serde_json::json!({
+            "message": reminder.message(Utc::now()),
+            "reminder": reminder,
+        });
+        self.client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+/// Mails reminders through an SMTP server.
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Mailbox,
+}
+
+impl EmailNotifier {
+    pub fn new(config: &EmailConfig) -> std::result::Result<Self, NotifyError> {
+        let mut transport = if config.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)
+        }
+        .port(config.smtp_port);
+        if !config.username.is_empty() {
+            transport = transport.credentials(Credentials::new(
+                config.username.clone(),
+                config.password.clone(),
+            ));
+        }
+
+        Ok(EmailNotifier {
+            transport: transport.build(),
+            from: config.from.parse()?,
+            to: config.to.parse()?,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn notify(&self, reminder: &Reminder) -> std::result::Result<(), NotifyError> {
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(self.to.clone())
+            .subject(reminder.subject())
+            .header(ContentType::TEXT_PLAIN)
+            .body(reminder.message(Utc::now()))?;
+        self.transport.send(email).await?;
+
+        Ok(())
+    }
+}
+
+/// The notifier chosen by the configuration.
+pub fn notifier(config: &RemindersConfig) -> std::result::Result<Box<dyn Notifier>, NotifyError> {
+    Ok(match config.notifier {
+        NotifierKind::Log => Box::new(LogNotifier),
+        NotifierKind::Webhook if config.webhook_url.is_empty() => {
+            return Err("the webhook notifier needs a webhook_url".into())
+        }
+        NotifierKind::Webhook => Box::new(WebhookNotifier::new(&config.webhook_url)),
+        NotifierKind::Email => Box::new(EmailNotifier::new(&config.email)?),
+    })
+}
+
+/// Runs [`remind`] every `interval_secs` for as long as the server runs.
+pub fn spawn(db: Db, config: RemindersConfig, notifier: Box<dyn Notifier>) {
+    rt::spawn(async move {
+        let mut interval = rt::time::interval(Duration::from_secs(config.interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            match remind(&db, &config.days_before, notifier.as_ref(), Utc::now()).await {
+                Ok(delivered) if !delivered.is_empty() => {
+                    log::info!("Delivered {} reminders", delivered.len())
+                }
+                Ok(_) => {}
+                Err(e) => log::error!("Could not look for maturing investments: {e}"),
+            }
+        }
+    });
+}
+
+/// Records the reminders due as of `now` and delivers every reminder not
+/// delivered yet, returning the ones that were.
+pub async fn remind(
+    db: &Db,
+    days_before: &[u32],
+    notifier: &dyn Notifier,
+    now: DateTime<Utc>,
+) -> Result<Vec<Reminder>> {
+    record_due(db, days_before, now).await?;
+
+    let mut response = db
+        .query("SELECT * FROM type::table($table) WHERE delivered_at = NONE ORDER BY created_at;")
+        .bind(("table", REMINDER))
+        .await?;
+    let pending: Vec<Reminder> = response.take(0)?;
+
+    let mut delivered = Vec::new();
+    for mut reminder in pending {
+        let Some(id) = reminder.id.clone() else {
+            continue;
+        };
+        // failed reminders stay undelivered and are tried again next time
+        if let Err(e) = notifier.notify(&reminder).await {
+            log::warn!("Could not deliver reminder {id}: {e}");
+            continue;
+        }
+        db.query("UPDATE $id SET delivered_at = $now;")
+            .bind(("id", Thing::from((id.tb.as_str(), id.id.as_str()))))
+            .bind(("now", now))
+            .await?
+            .check()?;
+        reminder.delivered_at = Some(now);
+        delivered.push(reminder);
+    }
+
+    Ok(delivered)
+}
+
+/// Records a reminder for every active investment that has entered a
+/// window it has no reminder for yet.
+async fn record_due(db: &Db, days_before: &[u32], now: DateTime<Utc>) -> Result<()> {
+    let Some(&widest) = days_before.iter().max() else {
+        return Ok(());
+    };
+    let today = now.date_naive();
+    let from = today.and_hms_opt(0, 0, 0).map(|start| start.and_utc());
+    let until = today
+        .checked_add_days(Days::new(u64::from(widest) + 1))
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map(|start| start.and_utc());
+
+    let mut response = db
+        .query(
+            "SELECT * FROM investment
+            WHERE deleted_at = NONE
+                AND (inv_status.status ?? 'active') = 'active'
+                AND end_date != NONE
+                AND <datetime> end_date >= <datetime> $from
+                AND <datetime> end_date < <datetime> $until;",
+        )
+        .bind(("from", from))
+        .bind(("until", until))
+        .await?;
+    let invs: Vec<Investment> = response.take(0)?;
+
+    for inv in invs {
+        let (Some(id), Some(end_date)) = (inv.id, inv.end_date) else {
+            continue;
+        };
+        let Some(window) = Reminder::due_window(days_before, end_date, now) else {
+            continue;
+        };
+        let key = Reminder::key(&id, end_date, window);
+        let existing: Option<Reminder> = db.select((REMINDER, key.as_str())).await?;
+        if existing.is_some() {
+            continue;
+        }
+
+        let reminder = Reminder {
+            id: None,
+            investment: id,
+            inv_name: inv.inv_name,
+            name: inv.name,
+            return_amount: inv.return_amount,
+            end_date,
+            days_before: window,
+            created_at: now,
+            delivered_at: None,
+        };
+        let _: Option<Reminder> = db
+            .create((REMINDER, key.as_str()))
+            .content(reminder)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Reminders recorded so far, newest first.
+pub async fn get_reminders(db: &Db) -> Result<Vec<Reminder>> {
+    let mut response = db
+        .query("SELECT * FROM type::table($table) ORDER BY created_at DESC;")
+        .bind(("table", REMINDER))
+        .await?;
+
+    Ok(response.take(0)?)
+}
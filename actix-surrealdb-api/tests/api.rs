@@ -0,0 +1,1438 @@
+//! Runs the API against a fresh in-memory database for every test.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use actix_web::http::StatusCode;
+use actix_web::{test, web, App};
+use async_trait::async_trait;
+use chrono::{Days, TimeZone, Utc};
+
+use actix_surrealdb_api::config::{DatabaseConfig, EmailConfig};
+use actix_surrealdb_api::maturity::mature_overdue;
+use actix_surrealdb_api::reminders::{remind, EmailNotifier, Notifier, NotifyError};
+use actix_surrealdb_api::{configure, connect, Db};
+use types::*;
+
+/// Starts the API with a database of its own, which `app!(db)` also
+/// returns.
+macro_rules! app {
+    () => {
+        app!(db).0
+    };
+    (db) => {{
+        let config = DatabaseConfig {
+            endpoint: "mem://".to_string(),
+            ..Default::default()
+        };
+        let db = connect(&config).await.expect("in-memory database");
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(db.clone()))
+                .configure(configure),
+        )
+        .await;
+        (app, db)
+    }};
+}
+
+fn investment(name: &str) -> Investment {
+    Investment {
+        inv_name: "Bank".to_string(),
+        inv_type: InvestmentType::Fd,
+        return_rate: Rate::from_bps(700),
+        return_type: ReturnType::Ordinary,
+        inv_amount: Money::from_major(100_000),
+        return_amount: Money::from_major(107_000),
+        name: name.to_string(),
+        start_date: Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+        end_date: Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+        ..Default::default()
+    }
+}
+
+/// Id of a record as used in URLs, e.g. `investment:abc`.
+fn url_id(inv: &Investment) -> String {
+    inv.id.as_ref().expect("a stored investment").to_string()
+}
+
+#[actix_web::test]
+async fn create_then_get() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let created: Investment = test::call_and_read_body_json(&app, req).await;
+    assert!(created.id.is_some());
+    assert!(created.created_at.is_some());
+    assert_eq!(created.name, "Asha");
+    assert_eq!(created.inv_amount, Money::from_major(100_000));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}", url_id(&created)))
+        .to_request();
+    let fetched: Investment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(fetched, created);
+}
+
+#[actix_web::test]
+async fn create_rejects_invalid_investment() {
+    let app = app!();
+
+    let mut inv = investment("Asha");
+    inv.inv_amount = Money::ZERO;
+    inv.end_date = inv.start_date;
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(inv)
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let error: ApiError = test::read_body_json(res).await;
+    assert_eq!(error.error, "validation");
+    assert!(error.fields.contains_key("inv-amount"));
+    assert!(error.fields.contains_key("end-date"));
+}
+
+#[actix_web::test]
+async fn created_investments_start_active() {
+    let app = app!();
+
+    for status in [
+        InvestmentStatus::Matured,
+        InvestmentStatus::Renewed,
+        InvestmentStatus::Closed,
+        InvestmentStatus::Withdrawn,
+    ] {
+        let mut inv = investment("Asha");
+        inv.inv_status = Some(InvStatus {
+            id: Some(RecordId::new("investment", "x")),
+            status,
+        });
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(inv)
+            .to_request();
+        let created: Investment = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(created.inv_status, None, "{status}");
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/inv/{}", url_id(&created)))
+            .to_request();
+        let fetched: Investment = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(fetched.status(), InvestmentStatus::Active, "{status}");
+    }
+}
+
+#[actix_web::test]
+async fn create_rejects_malformed_json() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .insert_header(("Content-Type", "application/json"))
+        .set_payload(r#"{"inv_name": "Bank""#)
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let error: ApiError = test::read_body_json(res).await;
+    assert_eq!(error.error, "bad_request");
+}
+
+#[actix_web::test]
+async fn get_rejects_bad_ids() {
+    let app = app!();
+
+    for id in ["abc", "task:abc", "investment:"] {
+        let req = test::TestRequest::get()
+            .uri(&format!("/inv/{id}"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "id {id}");
+    }
+}
+
+#[actix_web::test]
+async fn get_missing_investment() {
+    let app = app!();
+
+    let req = test::TestRequest::get()
+        .uri("/inv/investment:missing")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let error: ApiError = test::read_body_json(res).await;
+    assert_eq!(error.error, "not_found");
+}
+
+#[actix_web::test]
+async fn update_investment() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let mut inv: Investment = test::call_and_read_body_json(&app, req).await;
+
+    inv.name = "Asha Rao".to_string();
+    let req = test::TestRequest::patch()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .set_json(&inv)
+        .to_request();
+    let updated: Investment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(updated.id, inv.id);
+    assert_eq!(updated.name, "Asha Rao");
+}
+
+#[actix_web::test]
+async fn patch_only_changes_the_given_fields() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let inv: Investment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(inv.version, 1);
+
+    // server-managed fields are ignored
+    let req = test::TestRequest::patch()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .set_json(serde_json::json!({
+            "name": "Asha Rao",
+            "created_at": "2000-01-01T00:00:00Z",
+        }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("ETag").unwrap(), "\"2\"");
+    let updated: Investment = test::read_body_json(res).await;
+    assert_eq!(updated.name, "Asha Rao");
+    assert_eq!(updated.inv_name, inv.inv_name);
+    assert_eq!(updated.inv_amount, inv.inv_amount);
+    assert_eq!(updated.created_at, inv.created_at);
+    assert!(updated.updated_at > inv.updated_at);
+    assert_eq!(updated.version, 2);
+
+    // the patched investment is validated as a whole
+    let req = test::TestRequest::patch()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .set_json(serde_json::json!({ "inv_amount": Money::from_major(200_000) }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[actix_web::test]
+async fn patch_rejects_stale_versions() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let inv: Investment = test::call_and_read_body_json(&app, req).await;
+
+    let req = test::TestRequest::patch()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .insert_header(("If-Match", "\"1\""))
+        .set_json(serde_json::json!({ "name": "Asha Rao" }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // a second tab still editing version 1
+    let req = test::TestRequest::patch()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .insert_header(("If-Match", "\"1\""))
+        .set_json(serde_json::json!({ "name": "Ravi" }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    let error: ApiError = test::read_body_json(res).await;
+    assert_eq!(error.error, "conflict");
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.headers().get("ETag").unwrap(), "\"2\"");
+    let fetched: Investment = test::read_body_json(res).await;
+    assert_eq!(fetched.name, "Asha Rao");
+}
+
+#[actix_web::test]
+async fn history_of_changes() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .insert_header(("X-Actor", "asha@example.com"))
+        .set_json(investment("Asha"))
+        .to_request();
+    let mut inv: Investment = test::call_and_read_body_json(&app, req).await;
+
+    inv.name = "Asha Rao".to_string();
+    let req = test::TestRequest::patch()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .insert_header(("X-Actor", "ravi@example.com"))
+        .set_json(&inv)
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}/history", url_id(&inv)))
+        .to_request();
+    let history: Vec<AuditEntry> = test::call_and_read_body_json(&app, req).await;
+    let actions: Vec<AuditAction> = history.iter().map(|entry| entry.action).collect();
+    assert_eq!(
+        actions,
+        [
+            AuditAction::Create,
+            AuditAction::Update,
+            AuditAction::Delete
+        ]
+    );
+    assert!(history
+        .iter()
+        .all(|entry| Some(&entry.investment) == inv.id.as_ref()));
+
+    assert_eq!(history[0].actor, "asha@example.com");
+    assert!(history[0]
+        .changes
+        .iter()
+        .any(|change| change.field == "name" && change.to == "Asha"));
+
+    assert_eq!(history[1].actor, "ravi@example.com");
+    assert_eq!(
+        history[1].changes,
+        [FieldChange {
+            field: "name".to_string(),
+            from: "Asha".into(),
+            to: "Asha Rao".into(),
+        }]
+    );
+
+    assert_eq!(history[2].actor, "anonymous");
+    assert_eq!(history[2].changes.len(), 1);
+    assert_eq!(history[2].changes[0].field, "deleted_at");
+
+    let req = test::TestRequest::get()
+        .uri("/inv/investment:missing/history")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn update_requires_an_existing_id() {
+    let app = app!();
+
+    let req = test::TestRequest::patch()
+        .uri("/inv/investment:missing")
+        .set_json(serde_json::json!({ "name": "Asha" }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let inv: Investment = test::call_and_read_body_json(&app, req).await;
+
+    // the body can not name another investment than the path
+    let req = test::TestRequest::patch()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .set_json(serde_json::json!({ "id": "investment:other", "name": "Ravi" }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn legacy_routes_are_deprecated() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let mut inv: Investment = test::call_and_read_body_json(&app, req).await;
+    let successor = format!("</inv/{}>; rel=\"successor-version\"", url_id(&inv));
+
+    inv.name = "Asha Rao".to_string();
+    let req = test::TestRequest::patch()
+        .uri("/inv")
+        .set_json(&inv)
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("Deprecation").unwrap(), "true");
+    assert_eq!(res.headers().get("Link").unwrap(), successor.as_str());
+    assert!(res.headers().contains_key("Warning"));
+    let updated: Investment = test::read_body_json(res).await;
+    assert_eq!(updated.name, "Asha Rao");
+
+    // errors are marked too
+    let req = test::TestRequest::patch()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(res.headers().get("Deprecation").unwrap(), "true");
+
+    let req = test::TestRequest::delete()
+        .uri("/inv")
+        .set_json(&inv.id)
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get("Deprecation").unwrap(), "true");
+    assert_eq!(res.headers().get("Link").unwrap(), successor.as_str());
+    let deleted: Record = test::read_body_json(res).await;
+    assert_eq!(Some(deleted.id), inv.id);
+}
+
+#[actix_web::test]
+async fn delete_investment() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let inv: Investment = test::call_and_read_body_json(&app, req).await;
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .to_request();
+    let deleted: Record = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(Some(deleted.id), inv.id);
+
+    // a second delete finds nothing
+    let req = test::TestRequest::delete()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn trash_restore_and_purge() {
+    let app = app!();
+
+    let mut invs = Vec::new();
+    for name in ["Asha", "Ravi"] {
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(investment(name))
+            .to_request();
+        let inv: Investment = test::call_and_read_body_json(&app, req).await;
+        invs.push(inv);
+    }
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/inv/{}", url_id(&invs[0])))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // the deleted investment leaves the list and the summary for the trash
+    let req = test::TestRequest::get().uri("/invs").to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.total, 1);
+    assert_eq!(page.items[0].name, "Ravi");
+
+    let req = test::TestRequest::get().uri("/invs/summary").to_request();
+    let summary: PortfolioSummary = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(summary.total_invested, Money::from_major(100_000));
+
+    let req = test::TestRequest::get().uri("/invs/trash").to_request();
+    let trash: Vec<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(trash.len(), 1);
+    assert_eq!(trash[0].id, invs[0].id);
+    assert!(trash[0].deleted_at.is_some());
+
+    // only investments in the trash can be restored or purged
+    let req = test::TestRequest::post()
+        .uri(&format!("/inv/{}/restore", url_id(&invs[1])))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/inv/{}/purge", url_id(&invs[1])))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/inv/{}/restore", url_id(&invs[0])))
+        .to_request();
+    let restored: Investment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(restored.id, invs[0].id);
+    assert_eq!(restored.deleted_at, None);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}", url_id(&invs[0])))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // purging removes the investment for good
+    let req = test::TestRequest::delete()
+        .uri(&format!("/inv/{}", url_id(&invs[0])))
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = test::TestRequest::delete()
+        .uri(&format!("/inv/{}/purge", url_id(&invs[0])))
+        .to_request();
+    let purged: Record = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(Some(purged.id), invs[0].id);
+
+    let req = test::TestRequest::get().uri("/invs/trash").to_request();
+    let trash: Vec<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert!(trash.is_empty());
+
+    let req = test::TestRequest::post()
+        .uri(&format!("/inv/{}/restore", url_id(&invs[0])))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn renew_investment() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let old: Investment = test::call_and_read_body_json(&app, req).await;
+
+    let mut new = investment("Asha");
+    new.start_date = old.end_date;
+    new.end_date = Some(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap());
+    let req = test::TestRequest::post()
+        .uri(&format!("/inv/{}/renew", url_id(&old)))
+        .set_json(&new)
+        .to_request();
+    let renewal: Renewal = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(renewal.previous.id, old.id);
+    assert_eq!(renewal.previous.status(), InvestmentStatus::Renewed);
+    assert_ne!(renewal.renewed.id, old.id);
+    assert_eq!(renewal.renewed.status(), InvestmentStatus::Active);
+    assert_eq!(renewal.renewed.inv_status.unwrap().id, old.id);
+
+    // a renewed investment can not be renewed again
+    let req = test::TestRequest::post()
+        .uri(&format!("/inv/{}/renew", url_id(&old)))
+        .set_json(&new)
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let req = test::TestRequest::get().uri("/invs").to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.total, 2);
+
+    let req = test::TestRequest::post()
+        .uri("/inv/investment:missing/renew")
+        .set_json(&new)
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn investments_mature_then_are_paid_out() {
+    let (app, db) = app!(db);
+
+    let mut running = investment("Ravi");
+    running.end_date = Some(Utc::now() + Days::new(30));
+    let mut invs = Vec::new();
+    for inv in [investment("Asha"), running] {
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(inv)
+            .to_request();
+        let inv: Investment = test::call_and_read_body_json(&app, req).await;
+        invs.push(inv);
+    }
+
+    let matured = mature_overdue(&db, Utc::now()).await.expect("matured");
+    assert_eq!(matured.len(), 1);
+    assert_eq!(matured[0].id, invs[0].id);
+    assert!(mature_overdue(&db, Utc::now())
+        .await
+        .expect("matured")
+        .is_empty());
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}/history", url_id(&invs[0])))
+        .to_request();
+    let history: Vec<AuditEntry> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(history[1].actor, "system");
+    assert_eq!(
+        history[1].changes,
+        [FieldChange {
+            field: "inv_status".to_string(),
+            from: serde_json::Value::Null,
+            to: serde_json::json!({ "id": null, "status": "matured" }),
+        }]
+    );
+    // the change is audited when the record says it was made
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}", url_id(&invs[0])))
+        .to_request();
+    let stored: Investment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(stored.updated_at, Some(history[1].at));
+    assert_eq!(stored.updated_at, matured[0].updated_at);
+
+    let set_status = |inv: &Investment, status: InvestmentStatus| {
+        test::TestRequest::patch()
+            .uri(&format!("/inv/{}", url_id(inv)))
+            .set_json(serde_json::json!({ "inv_status": { "status": status } }))
+            .to_request()
+    };
+
+    // only matured investments are closed, anything running can be withdrawn
+    let res = test::call_service(&app, set_status(&invs[1], InvestmentStatus::Closed)).await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+    let res = test::call_service(&app, set_status(&invs[0], InvestmentStatus::Renewed)).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let req = set_status(&invs[0], InvestmentStatus::Withdrawn);
+    let withdrawn: Investment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(withdrawn.status(), InvestmentStatus::Withdrawn);
+    let res = test::call_service(&app, set_status(&invs[0], InvestmentStatus::Active)).await;
+    assert_eq!(res.status(), StatusCode::CONFLICT);
+
+    let req = test::TestRequest::get().uri("/invs/summary").to_request();
+    let summary: PortfolioSummary = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(summary.count(InvestmentStatus::Withdrawn), 1);
+    assert_eq!(summary.total_invested, Money::from_major(100_000));
+}
+
+#[actix_web::test]
+async fn renewal_chain() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let first: Investment = test::call_and_read_body_json(&app, req).await;
+
+    // renew it twice, each renewal starting when the previous one ends
+    let mut ids = vec![first.id.clone()];
+    let mut current = first;
+    for year in [2026, 2030] {
+        let mut next = investment("Asha");
+        next.start_date = current.end_date;
+        next.end_date = Some(Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0).unwrap());
+        let req = test::TestRequest::post()
+            .uri(&format!("/inv/{}/renew", url_id(&current)))
+            .set_json(&next)
+            .to_request();
+        let renewal: Renewal = test::call_and_read_body_json(&app, req).await;
+        ids.push(renewal.renewed.id.clone());
+        current = renewal.renewed;
+    }
+
+    // the whole chain is returned from any investment in it
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}/chain", ids[1].as_ref().unwrap()))
+        .to_request();
+    let chain: RenewalChain = test::call_and_read_body_json(&app, req).await;
+    let chain_ids: Vec<_> = chain
+        .links
+        .iter()
+        .map(|link| link.investment.id.clone())
+        .collect();
+    assert_eq!(chain_ids, ids);
+
+    // the first two investments have ended, the last one has not
+    let interest = Money::from_major(7_000);
+    assert_eq!(chain.links[0].cumulative_interest, interest);
+    assert_eq!(chain.links[1].cumulative_interest, interest + interest);
+    assert_eq!(chain.links[2].interest_earned, Money::ZERO);
+    assert_eq!(chain.total_interest_earned, interest + interest);
+
+    let req = test::TestRequest::get()
+        .uri("/inv/investment:missing/chain")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn list_filters_and_paginates() {
+    let app = app!();
+
+    for name in ["Asha", "Ravi", "Asha Rao"] {
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(investment(name))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert!(res.status().is_success());
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/invs?per_page=2")
+        .to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.total, 3);
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(page.page_count(), 2);
+
+    let req = test::TestRequest::get()
+        .uri("/invs?name=asha&sort=name&order=asc")
+        .to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<_> = page.items.iter().map(|inv| inv.name.as_str()).collect();
+    assert_eq!(names, ["Asha", "Asha Rao"]);
+
+    let req = test::TestRequest::get()
+        .uri("/invs?sort=colour")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn pages_of_equal_sort_values_neither_repeat_nor_skip_rows() {
+    let app = app!();
+
+    let mut names = Vec::new();
+    for i in 0..12 {
+        let name = format!("Asha {i}");
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(investment(&name))
+            .to_request();
+        test::call_service(&app, req).await;
+        names.push(name);
+    }
+
+    // every investment has the same type, so only the tiebreaker orders them
+    for order in ["asc", "desc"] {
+        let mut listed = Vec::new();
+        for page in 1..=4 {
+            let req = test::TestRequest::get()
+                .uri(&format!(
+                    "/invs?sort=inv_type&order={order}&per_page=3&page={page}"
+                ))
+                .to_request();
+            let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+            listed.extend(page.items.into_iter().map(|inv| inv.name));
+        }
+        listed.sort();
+        names.sort();
+        assert_eq!(listed, names, "{order}");
+    }
+}
+
+#[actix_web::test]
+async fn bulk_operations_apply_together() {
+    let app = app!();
+
+    let mut invs = Vec::new();
+    for name in ["Asha", "Ravi", "Meena"] {
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(investment(name))
+            .to_request();
+        let inv: Investment = test::call_and_read_body_json(&app, req).await;
+        invs.push(inv);
+    }
+    let id = |index: usize| invs[index].id.clone().expect("a stored investment");
+
+    let request = BulkRequest {
+        operations: vec![
+            BulkOperation::Create {
+                investment: Box::new(investment("Kiran")),
+            },
+            BulkOperation::Delete { id: id(0) },
+            BulkOperation::SetStatus {
+                id: id(1),
+                status: InvestmentStatus::Closed,
+            },
+            BulkOperation::AddTags {
+                id: id(2),
+                tags: vec![
+                    " sbi ".to_string(),
+                    "tax saver".to_string(),
+                    "sbi".to_string(),
+                ],
+            },
+        ],
+    };
+    let req = test::TestRequest::post()
+        .uri("/invs/bulk")
+        .set_json(&request)
+        .to_request();
+    let report: BulkReport = test::call_and_read_body_json(&app, req).await;
+    assert!(report.applied);
+    assert_eq!(report.results.len(), 4);
+    assert_eq!(report.errors().count(), 0);
+    assert!(report.results[0].id.is_some());
+    assert_eq!(report.results[1].id, Some(id(0)));
+
+    let req = test::TestRequest::get().uri("/invs").to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.total, 3);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}", url_id(&invs[1])))
+        .to_request();
+    let closed: Investment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(closed.status(), InvestmentStatus::Closed);
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}", url_id(&invs[2])))
+        .to_request();
+    let tagged: Investment = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(tagged.tags, ["sbi", "tax saver"]);
+    assert_eq!(tagged.version, 2);
+}
+
+#[actix_web::test]
+async fn bulk_operations_fail_together() {
+    let app = app!();
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    let inv: Investment = test::call_and_read_body_json(&app, req).await;
+    let id = inv.id.clone().expect("a stored investment");
+
+    let mut invalid = investment("Ravi");
+    invalid.inv_amount = Money::ZERO;
+    let request = BulkRequest {
+        operations: vec![
+            BulkOperation::Delete { id: id.clone() },
+            BulkOperation::Create {
+                investment: Box::new(invalid),
+            },
+            BulkOperation::Delete {
+                id: RecordId::new("investment", "missing"),
+            },
+        ],
+    };
+    let req = test::TestRequest::post()
+        .uri("/invs/bulk")
+        .set_json(&request)
+        .to_request();
+    let report: BulkReport = test::call_and_read_body_json(&app, req).await;
+    assert!(!report.applied);
+    let failed: Vec<usize> = report.errors().map(|result| result.index).collect();
+    assert_eq!(failed, [1, 2]);
+    let error = report.results[1].error.as_ref().unwrap();
+    assert_eq!(error.error, "validation");
+    assert!(error.fields.contains_key("inv-amount"));
+    assert_eq!(report.results[2].error.as_ref().unwrap().status, 404);
+
+    // the valid delete was not applied either
+    let req = test::TestRequest::get()
+        .uri(&format!("/inv/{}", url_id(&inv)))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let req = test::TestRequest::post()
+        .uri("/invs/bulk")
+        .set_json(BulkRequest::default())
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn import_previews_then_imports() {
+    let app = app!();
+
+    let csv = "\
+Bank,Holder,Type,Return Type,Amount,Return,Rate,From,To,Tags
+SBI,Asha,fd,Ordinary,\"1,00,000\",\"₹1,07,000\",7%,01-01-2024,01-01-2025,sbi;tax saver
+HDFC,Ravi,FD,Cumulative,50000,,7.5,01-04-2024,01-04-2025,
+";
+    let mut request = ImportRequest {
+        csv: csv.to_string(),
+        dry_run: true,
+        ..Default::default()
+    };
+    for (field, column) in [
+        ("inv_name", "Bank"),
+        ("name", "Holder"),
+        ("inv_type", "Type"),
+        ("return_type", "Return Type"),
+        ("inv_amount", "Amount"),
+        ("return_amount", "Return"),
+        ("return_rate", "Rate"),
+        ("start_date", "From"),
+        ("end_date", "To"),
+        ("tags", "Tags"),
+    ] {
+        request
+            .mapping
+            .insert(field.to_string(), column.to_string());
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/invs/import")
+        .set_json(&request)
+        .to_request();
+    let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+    assert!(!report.has_errors());
+    assert_eq!(report.imported, 0);
+    assert_eq!(report.rows.len(), 2);
+    assert_eq!(report.rows[0].line, 2);
+    assert_eq!(
+        report.rows[0].investment.inv_amount,
+        Money::from_major(100_000)
+    );
+    assert_eq!(report.rows[0].investment.tags, ["sbi", "tax saver"]);
+    let computed = report.rows[1].investment.maturity().unwrap().maturity_value;
+    assert_eq!(report.rows[1].investment.return_amount, computed);
+
+    let req = test::TestRequest::get().uri("/invs").to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.total, 0);
+
+    request.dry_run = false;
+    let req = test::TestRequest::post()
+        .uri("/invs/import")
+        .set_json(&request)
+        .to_request();
+    let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(report.imported, 2);
+    assert!(report.rows.iter().all(|row| row.investment.id.is_some()));
+
+    let req = test::TestRequest::get().uri("/invs").to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.total, 2);
+}
+
+#[actix_web::test]
+async fn import_reports_the_errors_of_every_row() {
+    let app = app!();
+
+    let csv = "\
+inv_name,name,inv_type,return_type,inv_amount,return_amount,return_rate,start_date,end_date
+SBI,Asha,FD,Ordinary,100000,107000,7,2024-01-01,2025-01-01
+SBI,Ravi,Bond,Ordinary,100000,90000,7,2024-01-01,someday
+";
+    let request = ImportRequest {
+        csv: csv.to_string(),
+        date_format: "%Y-%m-%d".to_string(),
+        ..Default::default()
+    };
+    let req = test::TestRequest::post()
+        .uri("/invs/import")
+        .set_json(&request)
+        .to_request();
+    let report: ImportReport = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(report.imported, 0);
+    assert!(report.rows[0].errors.is_empty());
+    let errors = &report.rows[1].errors;
+    assert!(errors.contains_key("inv-type"));
+    assert!(errors.contains_key("return-amount"));
+    assert!(errors.contains_key("end-date"));
+
+    // nothing is imported while a row has errors
+    let req = test::TestRequest::get().uri("/invs").to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.total, 0);
+
+    let request = ImportRequest {
+        csv: "inv_name,name\nSBI,Asha\n".to_string(),
+        ..Default::default()
+    };
+    let req = test::TestRequest::post()
+        .uri("/invs/import")
+        .set_json(&request)
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn export_in_every_format() {
+    let app = app!();
+
+    for name in ["Asha", "Ravi"] {
+        let mut inv = investment(name);
+        inv.tags = vec!["sbi".to_string(), "tax saver".to_string()];
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(inv)
+            .to_request();
+        test::call_service(&app, req).await;
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/invs/export?format=csv&name=asha")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res
+        .headers()
+        .get("Content-Type")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/csv"));
+    let disposition = res
+        .headers()
+        .get("Content-Disposition")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(disposition.starts_with("attachment"));
+    assert!(disposition.contains(".csv"));
+    let body = test::read_body(res).await;
+    let csv = std::str::from_utf8(&body).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "Start Date,End Date,Investment Name,Name,Investment Type,Return Type,Return Rate,Investment,Return,Tags",
+            "01-01-2024,01-01-2025,Bank,Asha,FD,Ordinary,7,100000,107000,sbi;tax saver",
+        ]
+    );
+
+    // every matching investment is exported, not just one page
+    let req = test::TestRequest::get()
+        .uri("/invs/export?format=json&per_page=1&sort=name")
+        .to_request();
+    let invs: Vec<Investment> = test::call_and_read_body_json(&app, req).await;
+    let names: Vec<&str> = invs.iter().map(|inv| inv.name.as_str()).collect();
+    assert_eq!(names, ["Asha", "Ravi"]);
+
+    let req = test::TestRequest::get()
+        .uri("/invs/export?format=xlsx")
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    // XLSX files are zip archives
+    assert!(body.starts_with(b"PK"));
+
+    let req = test::TestRequest::get()
+        .uri("/invs/export?format=pdf")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn csv_exports_do_not_run_formulas() {
+    let app = app!();
+
+    let mut inv = investment("+cmd|' /C calc'!A0");
+    inv.inv_name = "=HYPERLINK(\"http://example.com\")".to_string();
+    inv.tags = vec!["-1".to_string(), "@sum".to_string()];
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(inv)
+        .to_request();
+    test::call_service(&app, req).await;
+
+    let req = test::TestRequest::get()
+        .uri("/invs/export?format=csv")
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let mut reader = csv::Reader::from_reader(body.as_ref());
+    let record = reader.records().next().unwrap().unwrap();
+    assert_eq!(&record[2], "'=HYPERLINK(\"http://example.com\")");
+    assert_eq!(&record[3], "'+cmd|' /C calc'!A0");
+    assert_eq!(&record[9], "'-1;@sum");
+}
+
+#[actix_web::test]
+async fn calendar_feed_of_maturities() {
+    let app = app!();
+
+    let mut invs = Vec::new();
+    for name in ["Asha", "Ravi, Jr."] {
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(investment(name))
+            .to_request();
+        let inv: Investment = test::call_and_read_body_json(&app, req).await;
+        invs.push(inv);
+    }
+
+    let req = test::TestRequest::get()
+        .uri("/invs/calendar.ics")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res
+        .headers()
+        .get("Content-Type")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/calendar"));
+    let body = test::read_body(res).await;
+    let ics = std::str::from_utf8(&body).unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+    assert!(ics.contains(&format!("UID:{}@mone-goblin\r\n", url_id(&invs[0]))));
+    assert!(ics.contains("DTSTART;VALUE=DATE:20250101\r\nDTEND;VALUE=DATE:20250102\r\n"));
+    assert!(ics.contains("SUMMARY:Bank of Ravi\\, Jr. matures\r\n"));
+    assert!(ics.lines().all(|line| line.len() <= 75));
+
+    // the feed takes the filters of the list
+    let req = test::TestRequest::get()
+        .uri("/invs/calendar.ics?name=asha")
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    let ics = std::str::from_utf8(&body).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+}
+
+/// Notifier that keeps the reminders it is given, failing while `fail` is set.
+#[derive(Default)]
+struct RecordingNotifier {
+    sent: Mutex<Vec<Reminder>>,
+    fail: AtomicBool,
+}
+
+#[async_trait]
+impl Notifier for RecordingNotifier {
+    async fn notify(&self, reminder: &Reminder) -> Result<(), NotifyError> {
+        if self.fail.load(Ordering::SeqCst) {
+            return Err("unreachable".into());
+        }
+        self.sent.lock().unwrap().push(reminder.clone());
+        Ok(())
+    }
+}
+
+/// An investment ending `days` days from now.
+fn maturing_in(name: &str, days: u64) -> Investment {
+    let now = Utc::now();
+    Investment {
+        start_date: now.checked_sub_days(Days::new(365)),
+        end_date: now.checked_add_days(Days::new(days)),
+        ..investment(name)
+    }
+}
+
+#[actix_web::test]
+async fn reminders_fire_once_per_window() {
+    let (app, db) = app!(db);
+
+    for inv in [maturing_in("Asha", 5), maturing_in("Ravi", 60)] {
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(inv)
+            .to_request();
+        test::call_service(&app, req).await;
+    }
+
+    let windows = DEFAULT_REMINDER_DAYS;
+    let notifier = RecordingNotifier::default();
+    let now = Utc::now();
+    let delivered = remind(&db, &windows, &notifier, now).await.unwrap();
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].name, "Asha");
+    assert_eq!(delivered[0].days_before, 7);
+
+    // nothing new is due until the next window
+    let delivered = remind(&db, &windows, &notifier, now).await.unwrap();
+    assert!(delivered.is_empty());
+
+    let later = now.checked_add_days(Days::new(4)).unwrap();
+    let delivered = remind(&db, &windows, &notifier, later).await.unwrap();
+    let windows_sent: Vec<u32> = delivered.iter().map(|r| r.days_before).collect();
+    assert_eq!(windows_sent, [1]);
+    assert_eq!(notifier.sent.lock().unwrap().len(), 2);
+
+    // reminders that could not be delivered are tried again
+    let much_later = now.checked_add_days(Days::new(35)).unwrap();
+    notifier.fail.store(true, Ordering::SeqCst);
+    let delivered = remind(&db, &windows, &notifier, much_later).await.unwrap();
+    assert!(delivered.is_empty());
+    notifier.fail.store(false, Ordering::SeqCst);
+    let delivered = remind(&db, &windows, &notifier, much_later).await.unwrap();
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].name, "Ravi");
+    assert_eq!(delivered[0].days_before, 30);
+
+    let req = test::TestRequest::get().uri("/reminders").to_request();
+    let reminders: Vec<Reminder> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(reminders.len(), 3);
+    assert!(reminders.iter().all(|r| r.delivered_at.is_some()));
+}
+
+/// SMTP server that accepts one connection and returns the message it got.
+fn mock_smtp_server() -> (u16, std::thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let server = std::thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+        let mut message = String::new();
+        let mut in_data = false;
+
+        writer.write_all(b"220 localhost mock ESMTP\r\n").unwrap();
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 0 {
+            let reply: &[u8] = if in_data {
+                if line == ".\r\n" {
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else {
+                    message.push_str(&line);
+                    b""
+                }
+            } else {
+                match line.get(..4).map(str::to_ascii_uppercase).as_deref() {
+                    Some("EHLO") => b"250-localhost\r\n250 8BITMIME\r\n",
+                    Some("DATA") => {
+                        in_data = true;
+                        b"354 go ahead\r\n"
+                    }
+                    Some("QUIT") => {
+                        writer.write_all(b"221 bye\r\n").unwrap();
+                        break;
+                    }
+                    _ => b"250 ok\r\n",
+                }
+            };
+            writer.write_all(reply).unwrap();
+            line.clear();
+        }
+
+        message
+    });
+
+    (port, server)
+}
+
+#[actix_web::test]
+async fn email_reminders_go_through_smtp() {
+    let (port, server) = mock_smtp_server();
+    let config = EmailConfig {
+        smtp_host: "127.0.0.1".to_string(),
+        smtp_port: port,
+        starttls: false,
+        from: "Mone Goblin <mone@example.com>".to_string(),
+        to: "asha@example.com".to_string(),
+        ..Default::default()
+    };
+    let notifier = EmailNotifier::new(&config).unwrap();
+
+    let reminder = Reminder {
+        id: None,
+        investment: RecordId::new("investment", "abc"),
+        inv_name: "Bank".to_string(),
+        name: "Asha".to_string(),
+        return_amount: Money::from_major(107_000),
+        end_date: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+        days_before: 7,
+        created_at: Utc::now(),
+        delivered_at: None,
+    };
+    notifier.notify(&reminder).await.unwrap();
+
+    let message = server.join().unwrap();
+    assert!(message.contains("To: asha@example.com"));
+    assert!(message.contains("Subject: Bank of Asha matures on 01-01-2025"));
+}
+
+#[actix_web::test]
+async fn summary_totals() {
+    let app = app!();
+
+    for name in ["Asha", "Ravi"] {
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(investment(name))
+            .to_request();
+        test::call_service(&app, req).await;
+    }
+
+    let req = test::TestRequest::get().uri("/invs/summary").to_request();
+    let summary: PortfolioSummary = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(summary.total_invested, Money::from_major(200_000));
+    assert_eq!(summary.total_expected_return, Money::from_major(214_000));
+    assert_eq!(summary.count(InvestmentStatus::Active), 2);
+    // both matured at the start of 2025
+    assert_eq!(summary.total_interest_earned, Money::from_major(14_000));
+}
+
+/// Stores an investment without dates, as rows saved before they were
+/// required can be.
+async fn create_dateless(db: &Db, name: &str) {
+    let mut legacy = serde_json::to_value(Investment {
+        start_date: None,
+        end_date: None,
+        ..investment(name)
+    })
+    .unwrap();
+    legacy
+        .as_object_mut()
+        .unwrap()
+        .retain(|_, value| !value.is_null());
+    db.query("CREATE investment CONTENT $legacy")
+        .bind(("legacy", legacy))
+        .await
+        .and_then(|response| response.check())
+        .expect("legacy investment");
+}
+
+#[actix_web::test]
+async fn dateless_legacy_rows_are_left_out_of_date_checks() {
+    let (app, db) = app!(db);
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(investment("Asha"))
+        .to_request();
+    test::call_service(&app, req).await;
+    create_dateless(&db, "Old").await;
+
+    let req = test::TestRequest::get().uri("/invs/summary").to_request();
+    let summary: PortfolioSummary = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(summary.count(InvestmentStatus::Active), 2);
+    assert_eq!(summary.total_invested, Money::from_major(200_000));
+    // only the one that has an end date has earned its interest
+    assert_eq!(summary.total_interest_earned, Money::from_major(7_000));
+
+    let req = test::TestRequest::get().uri("/invs").to_request();
+    let page: Page<Investment> = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.total, 2);
+    let filters = [
+        "start_from=2023-12-01",
+        "start_to=2024-06-01",
+        "end_from=2024-06-01",
+        "end_to=2025-06-01",
+    ];
+    for filter in filters {
+        let req = test::TestRequest::get()
+            .uri(&format!("/invs?{filter}"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK, "{filter}");
+        let page: Page<Investment> = test::read_body_json(res).await;
+        assert_eq!(page.total, 1, "{filter}");
+        assert_eq!(page.items[0].name, "Asha", "{filter}");
+    }
+
+    let req = test::TestRequest::post()
+        .uri("/inv")
+        .set_json(maturing_in("Ravi", 5))
+        .to_request();
+    test::call_service(&app, req).await;
+    let notifier = RecordingNotifier::default();
+    let delivered = remind(&db, &DEFAULT_REMINDER_DAYS, &notifier, Utc::now())
+        .await
+        .expect("reminders");
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].name, "Ravi");
+
+    // the one without an end date is left active
+    let matured = mature_overdue(&db, Utc::now()).await.expect("matured");
+    let names: Vec<&str> = matured.iter().map(|inv| inv.name.as_str()).collect();
+    assert_eq!(names, ["Asha"]);
+}
+
+#[actix_web::test]
+async fn analytics_of_the_money_invested() {
+    let app = app!();
+
+    let mut sbi = investment("Asha");
+    sbi.institution = "SBI".to_string();
+    let mut rd = investment("Ravi");
+    rd.inv_type = InvestmentType::Rd;
+    rd.return_rate = Rate::from_bps(800);
+    rd.inv_amount = Money::from_major(300_000);
+    rd.return_amount = Money::from_major(324_000);
+    rd.start_date = Some(Utc::now());
+    rd.end_date = Some(Utc::now() + Days::new(365));
+    let mut withdrawn = investment("Meena");
+    withdrawn.institution = "SBI".to_string();
+    let mut invs = Vec::new();
+    for inv in [sbi, rd, withdrawn] {
+        let req = test::TestRequest::post()
+            .uri("/inv")
+            .set_json(inv)
+            .to_request();
+        let inv: Investment = test::call_and_read_body_json(&app, req).await;
+        invs.push(inv);
+    }
+    let req = test::TestRequest::patch()
+        .uri(&format!("/inv/{}", url_id(&invs[2])))
+        .set_json(serde_json::json!({ "inv_status": { "status": "withdrawn" } }))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let req = test::TestRequest::get().uri("/invs/analytics").to_request();
+    let analytics: PortfolioAnalytics = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(analytics.total_invested, Money::from_major(400_000));
+    let allocation = |allocations: &[Allocation]| {
+        allocations
+            .iter()
+            .map(|allocation| (allocation.label.clone(), allocation.invested))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        allocation(&analytics.by_type),
+        [
+            ("RD".to_string(), Money::from_major(300_000)),
+            ("FD".to_string(), Money::from_major(100_000)),
+        ]
+    );
+    // without an institution the investment name stands in for it
+    assert_eq!(
+        allocation(&analytics.by_institution),
+        [
+            ("Bank".to_string(), Money::from_major(300_000)),
+            ("SBI".to_string(), Money::from_major(100_000)),
+        ]
+    );
+    assert_eq!(analytics.by_holder.len(), 2);
+    assert_eq!(analytics.weighted_return_rate, Rate::from_bps(775));
+
+    // the whole interest of the RD is earned within the forecast, give or
+    // take the rounding of each month
+    assert_eq!(analytics.monthly_interest.len(), FORECAST_MONTHS as usize);
+    let interest: Money = analytics
+        .monthly_interest
+        .iter()
+        .map(|month| month.interest)
+        .sum();
+    assert!(interest <= Money::from_major(24_000));
+    assert!(interest > Money::from_major(23_999));
+}
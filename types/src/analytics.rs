@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Investment, Money, Rate};
+
+/// Number of months, the current one included, the interest is forecast for.
+pub const FORECAST_MONTHS: u32 = 24;
+
+/// Money invested in one group of investments, e.g. the FDs.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct Allocation {
+    pub label: String,
+    pub count: u64,
+    pub invested: Money,
+}
+
+/// Interest expected to be earned in one month.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct MonthlyInterest {
+    /// First day of the month.
+    pub month: NaiveDate,
+    pub interest: Money,
+}
+
+/// Analytics of the money currently invested, served by
+/// `GET /invs/analytics`. Like [`PortfolioSummary`](crate::PortfolioSummary)
+/// they leave out renewed, closed and withdrawn investments.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct PortfolioAnalytics {
+    pub total_invested: Money,
+    pub by_type: Vec<Allocation>,
+    /// By [`Investment::institution`], or by investment name for the ones
+    /// without an institution.
+    pub by_institution: Vec<Allocation>,
+    /// By holder name.
+    pub by_holder: Vec<Allocation>,
+    /// Interest of the [`FORECAST_MONTHS`] months from the current one, each
+    /// investment earning its interest evenly over its term.
+    pub monthly_interest: Vec<MonthlyInterest>,
+    /// Return rate of the portfolio, the rate of each investment weighted by
+    /// the amount invested in it.
+    pub weighted_return_rate: Rate,
+}
+
+impl PortfolioAnalytics {
+    /// Builds the analytics of `investments` as of `now`.
+    pub fn new<'a>(
+        investments: impl IntoIterator<Item = &'a Investment>,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let investments: Vec<&Investment> = investments
+            .into_iter()
+            .filter(|investment| !investment.status().is_final())
+            .collect();
+
+        let total_invested: Money = investments.iter().map(|inv| inv.inv_amount).sum();
+        let weighted: i128 = investments
+            .iter()
+            .map(|inv| i128::from(inv.return_rate.bps()) * i128::from(inv.inv_amount.minor()))
+            .sum();
+        let weighted_return_rate = match i128::from(total_invested.minor()) {
+            0 => Rate::ZERO,
+            total => Rate::from_bps(((weighted + total / 2) / total) as i32),
+        };
+
+        PortfolioAnalytics {
+            total_invested,
+            by_type: allocate(&investments, |inv| inv.inv_type.to_string()),
+            by_institution: allocate(&investments, |inv| {
+                if inv.institution.trim().is_empty() {
+                    inv.inv_name.trim().to_string()
+                } else {
+                    inv.institution.trim().to_string()
+                }
+            }),
+            by_holder: allocate(&investments, |inv| inv.name.trim().to_string()),
+            monthly_interest: forecast(&investments, now),
+            weighted_return_rate,
+        }
+    }
+}
+
+/// Groups the investments by `label`, largest amount first.
+fn allocate(investments: &[&Investment], label: impl Fn(&Investment) -> String) -> Vec<Allocation> {
+    let mut groups: HashMap<String, Allocation> = HashMap::new();
+    for investment in investments {
+        let label = label(investment);
+        let group = groups.entry(label.clone()).or_insert(Allocation {
+            label,
+            count: 0,
+            invested: Money::ZERO,
+        });
+        group.count += 1;
+        group.invested += investment.inv_amount;
+    }
+
+    let mut allocations: Vec<Allocation> = groups.into_values().collect();
+    allocations.sort_by(|a, b| b.invested.cmp(&a.invested).then(a.label.cmp(&b.label)));
+    allocations
+}
+
+fn forecast(investments: &[&Investment], now: DateTime<Utc>) -> Vec<MonthlyInterest> {
+    let today = now.date_naive();
+    let first = today.with_day(1).unwrap_or(today);
+
+    (0..FORECAST_MONTHS)
+        .filter_map(|offset| {
+            let month = first.checked_add_months(Months::new(offset))?;
+            let next = month.checked_add_months(Months::new(1))?;
+            let interest = investments
+                .iter()
+                .map(|investment| interest_between(investment, month, next))
+                .sum();
+            Some(MonthlyInterest { month, interest })
+        })
+        .collect()
+}
+
+/// Share of the interest of `investment` earned from `from` until `until`.
+fn interest_between(investment: &Investment, from: NaiveDate, until: NaiveDate) -> Money {
+    let (Some(start), Some(end)) = (investment.start_date, investment.end_date) else {
+        return Money::ZERO;
+    };
+    let (start, end) = (start.date_naive(), end.date_naive());
+    let term = (end - start).num_days();
+    let days = (end.min(until) - start.max(from)).num_days();
+    if term <= 0 || days <= 0 {
+        return Money::ZERO;
+    }
+
+    let interest = i128::from((investment.return_amount - investment.inv_amount).minor());
+    Money::from_minor((interest * i128::from(days) / i128::from(term)) as i64)
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{Investment, RecordId};
+
+/// Fields the API keeps up to date on every change, left out of the diffs.
+const UNTRACKED_FIELDS: [&str; 5] = [
+    "id",
+    "created_at",
+    "updated_at",
+    "version",
+    "return_mismatch",
+];
+
+/// What was done to an investment.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditAction {
+    Create,
+    Update,
+    /// The investment was moved to the trash.
+    Delete,
+    /// The investment was taken out of the trash.
+    Restore,
+    /// The investment was deleted for good.
+    Purge,
+    /// The investment was renewed into a new one.
+    Renew,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Restore => "restore",
+            AuditAction::Purge => "purge",
+            AuditAction::Renew => "renew",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A field of an investment and the values it had before and after a change,
+/// `null` when it had none.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+impl FieldChange {
+    /// The fields that differ between two versions of an investment, by
+    /// field name. A new investment has no `before`.
+    pub fn diff(before: Option<&Investment>, after: &Investment) -> Vec<FieldChange> {
+        let before = before.map(fields).unwrap_or_default();
+        let after = fields(after);
+
+        let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+        names
+            .into_iter()
+            .filter(|name| !UNTRACKED_FIELDS.contains(&name.as_str()))
+            .filter_map(|name| {
+                let from = before.get(name).cloned().unwrap_or(Value::Null);
+                let to = after.get(name).cloned().unwrap_or(Value::Null);
+                (from != to).then(|| FieldChange {
+                    field: name.clone(),
+                    from,
+                    to,
+                })
+            })
+            .collect()
+    }
+}
+
+fn fields(inv: &Investment) -> Map<String, Value> {
+    match serde_json::to_value(inv) {
+        Ok(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    }
+}
+
+/// One change to an investment, as served by `GET /inv/{id}/history`.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct AuditEntry {
+    pub id: Option<RecordId>,
+    pub investment: RecordId,
+    pub action: AuditAction,
+    /// Who made the change, as given by the `X-Actor` header.
+    pub actor: String,
+    pub changes: Vec<FieldChange>,
+    pub at: DateTime<Utc>,
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{ApiError, Investment, InvestmentStatus, RecordId};
+
+/// Most operations a single `POST /invs/bulk` can carry.
+pub const MAX_BULK_OPERATIONS: usize = 500;
+
+/// One operation of a `POST /invs/bulk`, tagged by `op`.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    Create {
+        investment: Box<Investment>,
+    },
+    /// Moves the investment to the trash.
+    Delete {
+        id: RecordId,
+    },
+    SetStatus {
+        id: RecordId,
+        status: InvestmentStatus,
+    },
+    /// Adds the tags the investment does not have yet.
+    AddTags {
+        id: RecordId,
+        tags: Vec<String>,
+    },
+}
+
+impl BulkOperation {
+    /// Investment the operation applies to; created ones have none yet.
+    pub fn id(&self) -> Option<&RecordId> {
+        match self {
+            BulkOperation::Create { .. } => None,
+            BulkOperation::Delete { id }
+            | BulkOperation::SetStatus { id, .. }
+            | BulkOperation::AddTags { id, .. } => Some(id),
+        }
+    }
+}
+
+/// Body of `POST /invs/bulk`. The operations are applied in order, in a
+/// single transaction: either all of them or none.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct BulkRequest {
+    pub operations: Vec<BulkOperation>,
+}
+
+/// Outcome of one operation of a bulk request.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct BulkItemResult {
+    /// Position of the operation in the request.
+    pub index: usize,
+    /// Investment the operation applied to, including the id of created ones.
+    pub id: Option<RecordId>,
+    /// Why the operation can not be applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ApiError>,
+}
+
+/// Response of `POST /invs/bulk`, with one result per operation.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct BulkReport {
+    /// Whether the operations were applied; they are not if any has an error.
+    pub applied: bool,
+    pub results: Vec<BulkItemResult>,
+}
+
+impl BulkReport {
+    /// Results of the operations that could not be applied.
+    pub fn errors(&self) -> impl Iterator<Item = &BulkItemResult> {
+        self.results.iter().filter(|result| result.error.is_some())
+    }
+}
+
+/// Trims the tags and drops empty and repeated ones, keeping their order.
+pub fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|seen| seen == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
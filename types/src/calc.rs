@@ -0,0 +1,495 @@
+//! Maturity value and interest calculations for FDs and RDs.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{Investment, InvestmentType, Money, ParseEnumError, Rate, ReturnType};
+
+/// A stored return amount may differ from the computed one by this much
+/// before it is flagged, to allow for the rounding conventions of each bank.
+pub const RETURN_TOLERANCE: Money = Money::from_major(1);
+
+/// ... or by this percentage of the computed interest, whichever is larger.
+pub const RETURN_TOLERANCE_PERCENT: i64 = 1;
+
+/// How often interest is compounded.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Compounding {
+    /// No compounding, interest is calculated on the principal only.
+    Simple,
+    Monthly,
+    Quarterly,
+    HalfYearly,
+    Yearly,
+}
+
+impl Compounding {
+    pub const ALL: [Compounding; 5] = [
+        Compounding::Simple,
+        Compounding::Monthly,
+        Compounding::Quarterly,
+        Compounding::HalfYearly,
+        Compounding::Yearly,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compounding::Simple => "Simple",
+            Compounding::Monthly => "Monthly",
+            Compounding::Quarterly => "Quarterly",
+            Compounding::HalfYearly => "Half-yearly",
+            Compounding::Yearly => "Yearly",
+        }
+    }
+
+    /// Length of one compounding period in months, `None` for simple interest.
+    pub fn period_months(&self) -> Option<u32> {
+        match self {
+            Compounding::Simple => None,
+            Compounding::Monthly => Some(1),
+            Compounding::Quarterly => Some(3),
+            Compounding::HalfYearly => Some(6),
+            Compounding::Yearly => Some(12),
+        }
+    }
+}
+
+impl fmt::Display for Compounding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Compounding {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Compounding::ALL
+            .into_iter()
+            .find(|c| c.as_str().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseEnumError {
+                kind: "Compounding",
+                value: s.to_string(),
+            })
+    }
+}
+
+/// What an investment is worth when it matures.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct Maturity {
+    /// Total amount deposited.
+    pub principal: Money,
+    pub interest: Money,
+    /// Principal plus interest.
+    pub maturity_value: Money,
+}
+
+impl Investment {
+    /// The compounding of the investment, falling back to what banks usually
+    /// do for its return type when none was chosen: payouts for ordinary
+    /// deposits are simple interest, cumulative ones compound quarterly.
+    pub fn compounding(&self) -> Compounding {
+        self.compounding.unwrap_or(match self.return_type {
+            ReturnType::Ordinary => Compounding::Simple,
+            ReturnType::Cumulative => Compounding::Quarterly,
+        })
+    }
+
+    /// Computes the maturity of the investment, or `None` if its dates are
+    /// missing or do not make a positive term.
+    pub fn maturity(&self) -> Option<Maturity> {
+        let start = self.start_date?.date_naive();
+        let end = self.end_date?.date_naive();
+        maturity(
+            self.inv_type,
+            self.inv_amount,
+            self.return_rate,
+            self.compounding(),
+            start,
+            end,
+        )
+    }
+
+    /// How far the stored return amount is from the computed maturity value,
+    /// if it is further than the tolerance.
+    pub fn return_mismatch(&self) -> Option<Money> {
+        let maturity = self.maturity()?;
+        let difference = self.return_amount - maturity.maturity_value;
+        let tolerance = RETURN_TOLERANCE.max(Money::from_minor(
+            maturity
+                .interest
+                .minor()
+                .saturating_mul(RETURN_TOLERANCE_PERCENT)
+                / 100,
+        ));
+
+        (difference.abs() > tolerance).then_some(difference)
+    }
+}
+
+/// Computes the maturity of a deposit of `principal` from `start` to `end`.
+///
+/// For an FD the whole principal is deposited on `start`. Interest compounds
+/// over every full period and simple interest is added for the days left
+/// over, as banks do. For an RD `principal` is the total deposited over the
+/// term, not the monthly instalment: it is split into one equal instalment
+/// per whole month, the first one paid on `start`.
+pub fn maturity(
+    inv_type: InvestmentType,
+    principal: Money,
+    rate: Rate,
+    compounding: Compounding,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Option<Maturity> {
+    if end <= start || !principal.is_positive() {
+        return None;
+    }
+
+    let value = match inv_type {
+        InvestmentType::Fd => fd_value(principal.minor() as f64, rate, compounding, start, end),
+        InvestmentType::Rd => rd_value(principal.minor() as f64, rate, compounding, start, end),
+    };
+    let maturity_value = Money::from_minor(value.round() as i64);
+
+    Some(Maturity {
+        principal,
+        interest: maturity_value - principal,
+        maturity_value,
+    })
+}
+
+fn fd_value(
+    principal: f64,
+    rate: Rate,
+    compounding: Compounding,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> f64 {
+    let rate = rate.as_fraction();
+    let Some(period) = compounding.period_months() else {
+        return principal * (1.0 + rate * year_fraction(start, end));
+    };
+
+    let periods = full_months(start, end) / period;
+    let compounded_until = add_months(start, periods * period);
+    let per_period = rate * f64::from(period) / 12.0;
+
+    principal
+        * (1.0 + per_period).powi(periods as i32)
+        * (1.0 + rate * year_fraction(compounded_until, end))
+}
+
+fn rd_value(
+    principal: f64,
+    rate: Rate,
+    compounding: Compounding,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> f64 {
+    let rate = rate.as_fraction();
+    let instalments = full_months(start, end).max(1);
+    let instalment = principal / f64::from(instalments);
+    let n = f64::from(instalments);
+
+    // The k-th instalment (from 0) earns interest for the n - k months left,
+    // so the instalments earn for n, n - 1, ..., 1 months between them.
+    match compounding.period_months() {
+        None => instalment * (n + rate / 12.0 * n * (n + 1.0) / 2.0),
+        Some(period) => {
+            let per_year = 12.0 / f64::from(period);
+            // growth of one instalment over a month
+            let growth = (1.0 + rate / per_year).powf(per_year / 12.0);
+            if growth == 1.0 {
+                return principal;
+            }
+            instalment * growth * (growth.powf(n) - 1.0) / (growth - 1.0)
+        }
+    }
+}
+
+/// Number of whole months from `start` to `end`.
+fn full_months(start: NaiveDate, end: NaiveDate) -> u32 {
+    let months = (end.year() - start.year()) * 12 + end.month() as i32 - start.month() as i32;
+    let months = u32::try_from(months).unwrap_or(0);
+    // the last month is not whole yet if its day has not come
+    if months > 0 && add_months(start, months) > end {
+        months - 1
+    } else {
+        months
+    }
+}
+
+fn add_months(date: NaiveDate, months: u32) -> NaiveDate {
+    date.checked_add_months(Months::new(months))
+        .unwrap_or(NaiveDate::MAX)
+}
+
+fn year_fraction(start: NaiveDate, end: NaiveDate) -> f64 {
+    (end - start).num_days() as f64 / 365.0
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn maturity_value(
+        inv_type: InvestmentType,
+        principal: Money,
+        rate: i32,
+        compounding: Compounding,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Money {
+        maturity(
+            inv_type,
+            principal,
+            Rate::from_bps(rate),
+            compounding,
+            start,
+            end,
+        )
+        .unwrap()
+        .maturity_value
+    }
+
+    /// An FD of `inv_amount` at 7% over 2023, returning `return_amount`.
+    fn fd_returning(inv_amount: Money, return_amount: Money) -> Investment {
+        Investment {
+            inv_type: InvestmentType::Fd,
+            return_type: ReturnType::Ordinary,
+            return_rate: Rate::from_bps(700),
+            inv_amount,
+            return_amount,
+            start_date: Some(Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap()),
+            end_date: Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            ..Investment::default()
+        }
+    }
+
+    #[test]
+    fn simple_fd_earns_interest_on_the_principal() {
+        let maturity = maturity(
+            InvestmentType::Fd,
+            Money::from_major(100_000),
+            Rate::from_bps(700),
+            Compounding::Simple,
+            date(2023, 1, 1),
+            date(2024, 1, 1),
+        )
+        .unwrap();
+
+        assert_eq!(maturity.principal, Money::from_major(100_000));
+        assert_eq!(maturity.interest, Money::from_major(7_000));
+        assert_eq!(maturity.maturity_value, Money::from_major(107_000));
+    }
+
+    #[test]
+    fn quarterly_fd_compounds_every_quarter() {
+        // 100000 × 1.02⁴
+        assert_eq!(
+            maturity_value(
+                InvestmentType::Fd,
+                Money::from_major(100_000),
+                800,
+                Compounding::Quarterly,
+                date(2023, 1, 1),
+                date(2024, 1, 1),
+            ),
+            Money::from_minor(10_824_322)
+        );
+    }
+
+    #[test]
+    fn monthly_fd_compounds_every_month() {
+        // 100000 × 1.01¹²
+        assert_eq!(
+            maturity_value(
+                InvestmentType::Fd,
+                Money::from_major(100_000),
+                1_200,
+                Compounding::Monthly,
+                date(2023, 1, 1),
+                date(2024, 1, 1),
+            ),
+            Money::from_minor(11_268_250)
+        );
+    }
+
+    #[test]
+    fn days_past_whole_periods_earn_simple_interest() {
+        // one quarter compounded, then 30 days of simple interest: 102000 × (1 + 0.08 × 30/365)
+        assert_eq!(
+            maturity_value(
+                InvestmentType::Fd,
+                Money::from_major(100_000),
+                800,
+                Compounding::Quarterly,
+                date(2023, 1, 1),
+                date(2023, 5, 1),
+            ),
+            Money::from_minor(10_267_068)
+        );
+        // less than a month: 100000 × (1 + 0.07 × 30/365)
+        assert_eq!(
+            maturity_value(
+                InvestmentType::Fd,
+                Money::from_major(100_000),
+                700,
+                Compounding::Simple,
+                date(2023, 1, 1),
+                date(2023, 1, 31),
+            ),
+            Money::from_minor(10_057_534)
+        );
+    }
+
+    #[test]
+    fn year_fraction_counts_days_over_365() {
+        // 2024 is a leap year, so it is 366/365 of a year
+        assert_eq!(
+            maturity_value(
+                InvestmentType::Fd,
+                Money::from_major(100_000),
+                700,
+                Compounding::Simple,
+                date(2024, 1, 1),
+                date(2025, 1, 1),
+            ),
+            Money::from_minor(10_701_918)
+        );
+    }
+
+    #[test]
+    fn rd_splits_the_total_into_monthly_instalments() {
+        // 12 instalments of 1000, earning 12, 11, … 1 months of interest
+        assert_eq!(
+            maturity_value(
+                InvestmentType::Rd,
+                Money::from_major(12_000),
+                600,
+                Compounding::Simple,
+                date(2023, 1, 1),
+                date(2024, 1, 1),
+            ),
+            Money::from_major(12_390)
+        );
+        assert_eq!(
+            maturity_value(
+                InvestmentType::Rd,
+                Money::from_major(12_000),
+                600,
+                Compounding::Quarterly,
+                date(2023, 1, 1),
+                date(2024, 1, 1),
+            ),
+            Money::from_minor(1_239_523)
+        );
+    }
+
+    #[test]
+    fn full_months_only_count_months_whose_day_has_come() {
+        assert_eq!(full_months(date(2023, 1, 15), date(2023, 1, 31)), 0);
+        assert_eq!(full_months(date(2023, 1, 15), date(2023, 2, 14)), 0);
+        assert_eq!(full_months(date(2023, 1, 15), date(2023, 2, 15)), 1);
+        assert_eq!(full_months(date(2023, 1, 15), date(2024, 1, 14)), 11);
+        assert_eq!(full_months(date(2023, 1, 15), date(2024, 1, 15)), 12);
+        // a month ending before the start day is whole on its last day
+        assert_eq!(full_months(date(2023, 1, 31), date(2023, 2, 28)), 1);
+        assert_eq!(full_months(date(2023, 1, 31), date(2023, 3, 30)), 1);
+        assert_eq!(full_months(date(2023, 1, 31), date(2023, 3, 31)), 2);
+    }
+
+    #[test]
+    fn full_months_of_far_terms_are_computed_at_once() {
+        assert_eq!(
+            full_months(date(2023, 1, 1), NaiveDate::MAX),
+            ((NaiveDate::MAX.year() - 2023) * 12 + 11) as u32
+        );
+
+        for inv_type in InvestmentType::ALL {
+            for compounding in Compounding::ALL {
+                let maturity = maturity(
+                    inv_type,
+                    Money::from_major(1_000),
+                    Rate::from_bps(700),
+                    compounding,
+                    date(2023, 1, 1),
+                    NaiveDate::MAX,
+                );
+                assert!(maturity.is_some(), "{inv_type} {compounding}");
+            }
+        }
+    }
+
+    #[test]
+    fn no_maturity_without_a_term_or_principal() {
+        let none = |principal: Money, start: NaiveDate, end: NaiveDate| {
+            maturity(
+                InvestmentType::Fd,
+                principal,
+                Rate::from_bps(700),
+                Compounding::Simple,
+                start,
+                end,
+            )
+        };
+
+        assert_eq!(
+            none(Money::from_major(1_000), date(2023, 1, 1), date(2023, 1, 1)),
+            None
+        );
+        assert_eq!(
+            none(Money::from_major(1_000), date(2024, 1, 1), date(2023, 1, 1)),
+            None
+        );
+        assert_eq!(none(Money::ZERO, date(2023, 1, 1), date(2024, 1, 1)), None);
+        assert_eq!(
+            Investment {
+                start_date: None,
+                ..fd_returning(Money::from_major(1_000), Money::from_major(1_070))
+            }
+            .maturity(),
+            None
+        );
+    }
+
+    #[test]
+    fn mismatch_allows_the_percentage_of_large_interest() {
+        // 1% of 7000 interest is 70, more than RETURN_TOLERANCE
+        let mismatch = |return_amount: i64| {
+            fd_returning(Money::from_major(100_000), Money::from_minor(return_amount))
+                .return_mismatch()
+        };
+
+        assert_eq!(mismatch(10_700_000), None);
+        assert_eq!(mismatch(10_707_000), None);
+        assert_eq!(mismatch(10_693_000), None);
+        assert_eq!(mismatch(10_707_001), Some(Money::from_minor(7_001)));
+        assert_eq!(mismatch(10_692_999), Some(Money::from_minor(-7_001)));
+    }
+
+    #[test]
+    fn mismatch_allows_at_least_the_fixed_tolerance() {
+        // 1% of 70 interest is 0.70, less than RETURN_TOLERANCE
+        let mismatch = |return_amount: i64| {
+            fd_returning(Money::from_major(1_000), Money::from_minor(return_amount))
+                .return_mismatch()
+        };
+
+        assert_eq!(mismatch(107_000 + RETURN_TOLERANCE.minor()), None);
+        assert_eq!(mismatch(107_000 - RETURN_TOLERANCE.minor()), None);
+        assert_eq!(mismatch(107_101), Some(Money::from_minor(101)));
+        assert_eq!(mismatch(106_899), Some(Money::from_minor(-101)));
+    }
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Investment, Money};
+
+/// One investment of a renewal chain.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct ChainLink {
+    pub investment: Investment,
+    /// Interest of this investment, if it has reached its end date.
+    pub interest_earned: Money,
+    /// Interest earned by this investment and every one before it.
+    pub cumulative_interest: Money,
+}
+
+/// An investment together with the ones it was renewed from and into, oldest
+/// first. Served by `GET /inv/{id}/chain`.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct RenewalChain {
+    pub links: Vec<ChainLink>,
+    pub total_interest_earned: Money,
+}
+
+impl RenewalChain {
+    /// Builds the chain from its investments, oldest first, counting the
+    /// interest of the ones that ended by `now`.
+    pub fn new(investments: impl IntoIterator<Item = Investment>, now: DateTime<Utc>) -> Self {
+        let mut chain = RenewalChain::default();
+
+        for investment in investments {
+            let interest_earned = match investment.end_date {
+                Some(end_date) if end_date <= now => {
+                    investment.return_amount - investment.inv_amount
+                }
+                _ => Money::ZERO,
+            };
+            chain.total_interest_earned += interest_earned;
+            chain.links.push(ChainLink {
+                investment,
+                interest_earned,
+                cumulative_interest: chain.total_interest_earned,
+            });
+        }
+
+        chain
+    }
+}
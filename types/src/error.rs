@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Error messages keyed by field, using the ids of the form fields, e.g.
+/// `"inv-amount"`.
+pub type FieldErrors = HashMap<String, String>;
+
+/// JSON body of every error response of the API.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct ApiError {
+    /// HTTP status code, or 0 when the request never got an answer.
+    pub status: u16,
+    /// Machine readable kind of error, e.g. `"not_found"`.
+    pub error: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fields: FieldErrors,
+}
+
+impl ApiError {
+    pub fn new(status: u16, error: &str, message: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            error: error.to_string(),
+            message: message.into(),
+            fields: FieldErrors::new(),
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        for (field, message) in &self.fields {
+            write!(f, "\n{field}: {message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ApiError {}
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{FieldErrors, Investment};
+
+/// Date format of imported files unless another one is given, the one used
+/// to show dates.
+pub const DEFAULT_DATE_FORMAT: &str = "%d-%m-%Y";
+
+/// Fields an import fills, and whether the file needs a column for them.
+/// Missing return amounts are computed, missing compoundings use the default
+/// of the return type, and tags are separated by `;`.
+pub const IMPORT_FIELDS: [(&str, bool); 12] = [
+    ("inv_name", true),
+    ("name", true),
+    ("institution", false),
+    ("inv_type", true),
+    ("return_type", true),
+    ("compounding", false),
+    ("inv_amount", true),
+    ("return_amount", false),
+    ("return_rate", true),
+    ("start_date", true),
+    ("end_date", true),
+    ("tags", false),
+];
+
+/// Body of `POST /invs/import`.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct ImportRequest {
+    /// The file, with a header row.
+    pub csv: String,
+    /// Header of the column of each field, by field name. Fields left out
+    /// are read from the column named like them.
+    #[serde(default)]
+    pub mapping: BTreeMap<String, String>,
+    /// `chrono` format of the dates, e.g. `%d-%m-%Y` or `%Y-%m-%d`.
+    #[serde(default = "default_date_format")]
+    pub date_format: String,
+    /// Only check the rows, without importing them.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_date_format() -> String {
+    DEFAULT_DATE_FORMAT.to_string()
+}
+
+impl Default for ImportRequest {
+    fn default() -> Self {
+        ImportRequest {
+            csv: String::new(),
+            mapping: BTreeMap::new(),
+            date_format: default_date_format(),
+            dry_run: false,
+        }
+    }
+}
+
+impl ImportRequest {
+    /// Header of the column that holds `field`.
+    pub fn column<'a>(&'a self, field: &'a str) -> &'a str {
+        self.mapping
+            .get(field)
+            .map(|column| column.trim())
+            .filter(|column| !column.is_empty())
+            .unwrap_or(field)
+    }
+}
+
+/// One row of an imported file.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct ImportRow {
+    /// Line of the row in the file, counting the header as line 1.
+    pub line: u64,
+    /// The investment read from the row, with its id once imported.
+    pub investment: Investment,
+    /// What is wrong with the row, keyed like the form fields.
+    #[serde(default)]
+    pub errors: FieldErrors,
+}
+
+/// Response of `POST /invs/import`.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Number of investments imported; none are if any row has errors.
+    pub imported: usize,
+    pub rows: Vec<ImportRow>,
+}
+
+impl ImportReport {
+    pub fn has_errors(&self) -> bool {
+        self.rows.iter().any(|row| !row.errors.is_empty())
+    }
+}
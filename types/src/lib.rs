@@ -1,32 +1,274 @@
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use surrealdb::sql::Thing;
 
-#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+mod analytics;
+mod audit;
+mod bulk;
+pub mod calc;
+mod chain;
+mod error;
+mod import;
+mod lifecycle;
+mod money;
+mod query;
+mod record_id;
+mod reminder;
+mod summary;
+pub mod validation;
+
+pub use analytics::*;
+pub use audit::*;
+pub use bulk::*;
+pub use calc::{Compounding, Maturity};
+pub use chain::*;
+pub use error::*;
+pub use import::*;
+pub use lifecycle::*;
+pub use money::*;
+pub use query::*;
+pub use record_id::*;
+pub use reminder::*;
+pub use summary::*;
+
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
 pub struct Investment {
-    pub id: Option<Thing>,
+    pub id: Option<RecordId>,
     pub inv_name: String,
-    pub inv_type: String,
-    pub return_rate: i32,
-    pub return_type: String,
-    pub inv_amount: i32,
-    pub return_amount: i32,
+    pub inv_type: InvestmentType,
+    pub return_rate: Rate,
+    pub return_type: ReturnType,
+    /// Compounding used to compute the return amount, see [`Investment::compounding`].
+    #[serde(default)]
+    pub compounding: Option<Compounding>,
+    /// Total amount deposited; for an RD the sum of its monthly instalments.
+    pub inv_amount: Money,
+    pub return_amount: Money,
     pub name: String,
+    /// Bank or company the money is deposited with; may be left blank.
+    #[serde(default)]
+    pub institution: String,
     pub inv_status: Option<InvStatus>,
+    /// Free-form labels, e.g. the bank branch or what the money is for.
+    #[serde(default)]
+    pub tags: Vec<String>,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
     pub created_at: Option<DateTime<Utc>>,
     pub updated_at: Option<DateTime<Utc>>,
+    /// Bumped by the API on every change. Updates can send it back in an
+    /// `If-Match` header so that they fail instead of overwriting a newer one.
+    #[serde(default)]
+    pub version: u64,
+    /// When the investment was moved to the trash; `None` for live ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Set by the API on the records it returns when the stored return amount
+    /// is off from the computed one, see [`Investment::return_mismatch`].
+    /// Never stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_mismatch: Option<Money>,
+}
+
+impl Investment {
+    /// Status of the investment as stored; investments that never changed
+    /// status have none and are active.
+    pub fn status(&self) -> InvestmentStatus {
+        self.inv_status
+            .as_ref()
+            .map(|inv_status| inv_status.status)
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
 pub struct InvStatus {
-    pub id: Option<Thing>,
-    pub status: String,
+    /// For investments created by renewing another one, that investment.
+    pub id: Option<RecordId>,
+    pub status: InvestmentStatus,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Record {
     #[allow(dead_code)]
-    pub id: Thing,
+    pub id: RecordId,
+}
+
+/// Result of renewing an investment.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct Renewal {
+    /// The investment that was renewed, now with the renewed status.
+    pub previous: Investment,
+    /// The new investment it was renewed into.
+    pub renewed: Investment,
+}
+
+/// The kind of deposit an investment is.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Deserialize, Serialize)]
+pub enum InvestmentType {
+    /// Fixed deposit, the whole amount is deposited up front.
+    #[default]
+    #[serde(rename = "FD")]
+    Fd,
+    /// Recurring deposit, the amount is deposited in monthly instalments.
+    #[serde(rename = "RD")]
+    Rd,
+}
+
+impl InvestmentType {
+    pub const ALL: [InvestmentType; 2] = [InvestmentType::Fd, InvestmentType::Rd];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InvestmentType::Fd => "FD",
+            InvestmentType::Rd => "RD",
+        }
+    }
+}
+
+/// How the interest of an investment is paid out.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Deserialize, Serialize)]
+pub enum ReturnType {
+    /// Interest is paid out periodically.
+    #[default]
+    Ordinary,
+    /// Interest is reinvested and paid out on maturity.
+    ///
+    /// Older records were saved with the misspelt "Culmulative", which is
+    /// still accepted when reading them back.
+    #[serde(alias = "Culmulative")]
+    Cumulative,
+}
+
+impl ReturnType {
+    pub const ALL: [ReturnType; 2] = [ReturnType::Ordinary, ReturnType::Cumulative];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReturnType::Ordinary => "Ordinary",
+            ReturnType::Cumulative => "Cumulative",
+        }
+    }
+}
+
+/// Where an investment is in its life, see [`InvestmentStatus::next`] for how
+/// it moves from one to another.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InvestmentStatus {
+    /// The investment is running until its end date.
+    #[default]
+    Active,
+    /// The investment has reached its end date and waits to be renewed or
+    /// paid out.
+    Matured,
+    /// The investment has been renewed into a new one.
+    Renewed,
+    /// The investment has been paid out on maturity.
+    Closed,
+    /// The investment has been paid out, before maturity or after it.
+    Withdrawn,
+}
+
+impl InvestmentStatus {
+    pub const ALL: [InvestmentStatus; 5] = [
+        InvestmentStatus::Active,
+        InvestmentStatus::Matured,
+        InvestmentStatus::Renewed,
+        InvestmentStatus::Closed,
+        InvestmentStatus::Withdrawn,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InvestmentStatus::Active => "active",
+            InvestmentStatus::Matured => "matured",
+            InvestmentStatus::Renewed => "renewed",
+            InvestmentStatus::Closed => "closed",
+            InvestmentStatus::Withdrawn => "withdrawn",
+        }
+    }
+}
+
+/// Error returned when a string does not name a variant of one of the enums above.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseEnumError {
+    kind: &'static str,
+    value: String,
+}
+
+impl fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a valid {}", self.value, self.kind)
+    }
+}
+
+impl std::error::Error for ParseEnumError {}
+
+impl FromStr for InvestmentType {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "FD" => Ok(InvestmentType::Fd),
+            "RD" => Ok(InvestmentType::Rd),
+            _ => Err(ParseEnumError {
+                kind: "Investment Type",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl FromStr for ReturnType {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Ordinary" => Ok(ReturnType::Ordinary),
+            "Cumulative" | "Culmulative" => Ok(ReturnType::Cumulative),
+            _ => Err(ParseEnumError {
+                kind: "Return Type",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl FromStr for InvestmentStatus {
+    type Err = ParseEnumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(InvestmentStatus::Active),
+            "matured" => Ok(InvestmentStatus::Matured),
+            "renewed" => Ok(InvestmentStatus::Renewed),
+            "closed" => Ok(InvestmentStatus::Closed),
+            "withdrawn" => Ok(InvestmentStatus::Withdrawn),
+            _ => Err(ParseEnumError {
+                kind: "Status",
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for InvestmentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for ReturnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for InvestmentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
@@ -0,0 +1,236 @@
+//! Lifecycle of an investment:
+//!
+//! ```text
+//! active ──> matured ──> renewed
+//!   │           ├──────> closed
+//!   └───────────┴──────> withdrawn
+//! ```
+//!
+//! Investments mature on their end date. Once matured they are renewed into
+//! a new investment or paid out, and can be withdrawn at any time before.
+
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::{Investment, InvestmentStatus};
+
+impl InvestmentStatus {
+    /// Statuses an investment can move to from this one.
+    pub fn next(self) -> &'static [InvestmentStatus] {
+        match self {
+            InvestmentStatus::Active => &[InvestmentStatus::Matured, InvestmentStatus::Withdrawn],
+            InvestmentStatus::Matured => &[
+                InvestmentStatus::Renewed,
+                InvestmentStatus::Closed,
+                InvestmentStatus::Withdrawn,
+            ],
+            InvestmentStatus::Renewed | InvestmentStatus::Closed | InvestmentStatus::Withdrawn => {
+                &[]
+            }
+        }
+    }
+
+    pub fn can_become(self, next: InvestmentStatus) -> bool {
+        self.next().contains(&next)
+    }
+
+    /// Moves to `next`, if an investment can go from this status to it.
+    pub fn transition(self, next: InvestmentStatus) -> Result<InvestmentStatus, TransitionError> {
+        if self.can_become(next) {
+            Ok(next)
+        } else {
+            Err(TransitionError {
+                from: self,
+                to: next,
+            })
+        }
+    }
+
+    /// Whether the investment is over, its money no longer invested in it.
+    pub fn is_final(self) -> bool {
+        self.next().is_empty()
+    }
+}
+
+impl Investment {
+    /// Status of the investment as of `now`. An active investment past its
+    /// end date has matured, whether or not it has been marked so yet.
+    pub fn status_as_of(&self, now: DateTime<Utc>) -> InvestmentStatus {
+        match (self.status(), self.end_date) {
+            (InvestmentStatus::Active, Some(end_date)) if end_date <= now => {
+                InvestmentStatus::Matured
+            }
+            (status, _) => status,
+        }
+    }
+
+    /// Moves the investment to `next` as of `now`.
+    pub fn transition(
+        &self,
+        next: InvestmentStatus,
+        now: DateTime<Utc>,
+    ) -> Result<InvestmentStatus, TransitionError> {
+        let current = self.status_as_of(now);
+        // a matured investment that has not been marked so yet may still be
+        // marked now
+        if current == next && self.status() != next {
+            return Ok(next);
+        }
+        current.transition(next)
+    }
+}
+
+/// Error returned for a status an investment can not move to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TransitionError {
+    pub from: InvestmentStatus,
+    pub to: InvestmentStatus,
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "an investment that is {} can not become {}",
+            self.from, self.to
+        )
+    }
+}
+
+impl std::error::Error for TransitionError {}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::InvStatus;
+
+    use InvestmentStatus::*;
+
+    /// Every move the lifecycle allows.
+    const ALLOWED: [(InvestmentStatus, InvestmentStatus); 5] = [
+        (Active, Matured),
+        (Active, Withdrawn),
+        (Matured, Renewed),
+        (Matured, Closed),
+        (Matured, Withdrawn),
+    ];
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap()
+    }
+
+    /// An investment stored with `status` that ends on `end_date`.
+    fn investment(status: InvestmentStatus, end_date: Option<DateTime<Utc>>) -> Investment {
+        Investment {
+            inv_status: Some(InvStatus { id: None, status }),
+            end_date,
+            ..Investment::default()
+        }
+    }
+
+    #[test]
+    fn allowed_transitions_succeed() {
+        for (from, to) in ALLOWED {
+            assert!(from.can_become(to), "{from} -> {to}");
+            assert_eq!(from.transition(to), Ok(to), "{from} -> {to}");
+        }
+    }
+
+    #[test]
+    fn every_other_transition_is_rejected() {
+        for from in InvestmentStatus::ALL {
+            for to in InvestmentStatus::ALL {
+                if ALLOWED.contains(&(from, to)) {
+                    continue;
+                }
+                assert!(!from.can_become(to), "{from} -> {to}");
+                assert_eq!(
+                    from.transition(to),
+                    Err(TransitionError { from, to }),
+                    "{from} -> {to}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn renewed_closed_and_withdrawn_are_final() {
+        let finals: Vec<InvestmentStatus> = InvestmentStatus::ALL
+            .into_iter()
+            .filter(|status| status.is_final())
+            .collect();
+        assert_eq!(finals, [Renewed, Closed, Withdrawn]);
+    }
+
+    #[test]
+    fn active_investments_past_their_end_date_have_matured() {
+        let ended = investment(Active, Some(now()));
+        assert_eq!(ended.status_as_of(now()), Matured);
+
+        let running = investment(Active, Some(now() + chrono::Days::new(1)));
+        assert_eq!(running.status_as_of(now()), Active);
+
+        let dateless = investment(Active, None);
+        assert_eq!(dateless.status_as_of(now()), Active);
+
+        // only active investments mature by date
+        let closed = investment(Closed, Some(now()));
+        assert_eq!(closed.status_as_of(now()), Closed);
+    }
+
+    #[test]
+    fn ended_investments_can_be_paid_out_before_being_marked_matured() {
+        let ended = investment(Active, Some(now()));
+        for to in [Matured, Renewed, Closed, Withdrawn] {
+            assert_eq!(ended.transition(to, now()), Ok(to), "{to}");
+        }
+        assert_eq!(
+            ended.transition(Active, now()),
+            Err(TransitionError {
+                from: Matured,
+                to: Active
+            })
+        );
+    }
+
+    #[test]
+    fn running_investments_can_not_be_paid_out() {
+        let running = investment(Active, Some(now() + chrono::Days::new(1)));
+        for to in [Renewed, Closed] {
+            assert_eq!(
+                running.transition(to, now()),
+                Err(TransitionError { from: Active, to }),
+                "{to}"
+            );
+        }
+        assert_eq!(running.transition(Matured, now()), Ok(Matured));
+        assert_eq!(running.transition(Withdrawn, now()), Ok(Withdrawn));
+    }
+
+    #[test]
+    fn matured_investments_do_not_mature_again() {
+        let matured = investment(Matured, Some(now()));
+        assert_eq!(
+            matured.transition(Matured, now()),
+            Err(TransitionError {
+                from: Matured,
+                to: Matured
+            })
+        );
+    }
+
+    #[test]
+    fn transition_error_names_both_statuses() {
+        let error = TransitionError {
+            from: Closed,
+            to: Active,
+        };
+        assert_eq!(
+            error.to_string(),
+            "an investment that is closed can not become active"
+        );
+    }
+}
@@ -0,0 +1,424 @@
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// An amount of money stored exactly, in minor units (paise).
+///
+/// It is serialized as a plain integer number of paise, so ₹1,00,000.50 goes
+/// over the wire and into the database as `10000050`. Arithmetic saturates at
+/// the bounds of `i64` instead of overflowing, so a total of many large
+/// amounts can not panic or wrap around.
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Deserialize, Serialize,
+)]
+#[serde(transparent)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_minor(paise: i64) -> Self {
+        Money(paise)
+    }
+
+    pub const fn from_major(rupees: i64) -> Self {
+        Money(rupees * 100)
+    }
+
+    pub const fn minor(self) -> i64 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    pub fn abs(self) -> Self {
+        Money(self.0.saturating_abs())
+    }
+
+    /// Formats the amount in rupees with Indian digit grouping, e.g.
+    /// `5,00,000` or `12,34,567.50`.
+    pub fn to_indian_string(self) -> String {
+        let paise = self.0.unsigned_abs();
+        let whole = (paise / 100).to_string();
+        let fraction = (!paise.is_multiple_of(100)).then(|| format!("{:02}", paise % 100));
+
+        let mut grouped = String::new();
+        if self.0 < 0 {
+            grouped.push('-');
+        }
+        // The last three digits form a group, every two digits before them another.
+        for (i, digit) in whole.chars().enumerate() {
+            let remaining = whole.len() - i;
+            if i > 0 && remaining >= 3 && (remaining - 3).is_multiple_of(2) {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            grouped.push('.');
+            grouped.push_str(&fraction);
+        }
+        grouped
+    }
+}
+
+/// Prints the amount in rupees, with two decimals only when there are paise,
+/// e.g. `100000` or `100000.50`.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let paise = self.0.unsigned_abs();
+        if paise.is_multiple_of(100) {
+            write!(f, "{sign}{}", paise / 100)
+        } else {
+            write!(f, "{sign}{}.{:02}", paise / 100, paise % 100)
+        }
+    }
+}
+
+/// Parses an amount in rupees with at most two decimals. Digit grouping
+/// commas are ignored, so both `100000.5` and `1,00,000.50` are accepted.
+impl FromStr for Money {
+    type Err = ParseDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_fixed(&s.replace(',', ""), 2)
+            .map(Money)
+            .ok_or_else(|| ParseDecimalError {
+                kind: "amount",
+                value: s.to_string(),
+            })
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Money {
+        Money(self.0.saturating_neg())
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        iter.fold(Money::ZERO, Add::add)
+    }
+}
+
+/// An annual interest rate stored exactly, in basis points (hundredths of a
+/// percent).
+///
+/// It is serialized as a plain integer number of basis points, so 7.25% goes
+/// over the wire and into the database as `725`.
+#[derive(
+    Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Deserialize, Serialize,
+)]
+#[serde(transparent)]
+pub struct Rate(i32);
+
+impl Rate {
+    pub const ZERO: Rate = Rate(0);
+
+    pub const fn from_bps(bps: i32) -> Self {
+        Rate(bps)
+    }
+
+    pub const fn bps(self) -> i32 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    /// The rate as a fraction, e.g. `0.0725` for 7.25%.
+    pub fn as_fraction(self) -> f64 {
+        f64::from(self.0) / 10_000.0
+    }
+}
+
+/// Prints the rate as a percentage without trailing zeros, e.g. `7`, `7.5`
+/// or `7.25`.
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let bps = self.0.unsigned_abs();
+        match bps % 100 {
+            0 => write!(f, "{sign}{}", bps / 100),
+            fraction if fraction.is_multiple_of(10) => {
+                write!(f, "{sign}{}.{}", bps / 100, fraction / 10)
+            }
+            fraction => write!(f, "{sign}{}.{:02}", bps / 100, fraction),
+        }
+    }
+}
+
+/// Parses a percentage with at most two decimals, e.g. `7.25`.
+impl FromStr for Rate {
+    type Err = ParseDecimalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_fixed(s.trim_end_matches('%'), 2)
+            .and_then(|bps| i32::try_from(bps).ok())
+            .map(Rate)
+            .ok_or_else(|| ParseDecimalError {
+                kind: "rate",
+                value: s.to_string(),
+            })
+    }
+}
+
+/// Error returned when a string is not a valid [`Money`] or [`Rate`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseDecimalError {
+    kind: &'static str,
+    value: String,
+}
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' is not a valid {}, use at most two decimals",
+            self.value, self.kind
+        )
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+/// Parses a decimal string into an integer scaled by `10^scale`, rejecting
+/// anything with more than `scale` decimals.
+fn parse_fixed(s: &str, scale: u32) -> Option<i64> {
+    let s = s.trim();
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+    if whole.is_empty() && fraction.is_empty()
+        || fraction.len() > scale as usize
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let whole: i64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let fraction: i64 = format!("{fraction:0<width$}", width = scale as usize)
+        .parse()
+        .ok()?;
+    let value = whole
+        .checked_mul(10_i64.pow(scale))?
+        .checked_add(fraction)?;
+
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fixed_scales_decimals() {
+        assert_eq!(parse_fixed("12.34", 2), Some(1234));
+        assert_eq!(parse_fixed("12.3", 2), Some(1230));
+        assert_eq!(parse_fixed("12", 2), Some(1200));
+        assert_eq!(parse_fixed("12.", 2), Some(1200));
+        assert_eq!(parse_fixed(".5", 2), Some(50));
+        assert_eq!(parse_fixed(" 7 ", 2), Some(700));
+    }
+
+    #[test]
+    fn parse_fixed_reads_negatives() {
+        assert_eq!(parse_fixed("-12.34", 2), Some(-1234));
+        assert_eq!(parse_fixed("-.05", 2), Some(-5));
+        assert_eq!(parse_fixed("--1", 2), None);
+        assert_eq!(parse_fixed("1-", 2), None);
+    }
+
+    #[test]
+    fn parse_fixed_rejects_more_decimals_than_the_scale() {
+        assert_eq!(parse_fixed("1.234", 2), None);
+        assert_eq!(parse_fixed("1.000", 2), None);
+        assert_eq!(parse_fixed("1.2", 0), None);
+    }
+
+    #[test]
+    fn parse_fixed_rejects_empty_and_malformed_strings() {
+        for s in [
+            "", " ", "-", ".", "-.", "abc", "1e3", "1.2.3", "+1", "1 000",
+        ] {
+            assert_eq!(parse_fixed(s, 2), None, "{s:?}");
+        }
+    }
+
+    #[test]
+    fn parse_fixed_rejects_overflow() {
+        assert_eq!(parse_fixed("92233720368547758.07", 2), Some(i64::MAX));
+        assert_eq!(parse_fixed("92233720368547758.08", 2), None);
+        assert_eq!(parse_fixed("92233720368547759", 2), None);
+        assert_eq!(parse_fixed("99999999999999999999", 2), None);
+    }
+
+    #[test]
+    fn money_parses_rupees_with_grouping() {
+        assert_eq!("100000".parse(), Ok(Money::from_major(100_000)));
+        assert_eq!("1,00,000.50".parse(), Ok(Money::from_minor(10_000_050)));
+        assert_eq!("-250.75".parse(), Ok(Money::from_minor(-25_075)));
+    }
+
+    #[test]
+    fn money_rejects_invalid_amounts() {
+        for s in ["", ",", "1.005", "12abc", "92233720368547758.08"] {
+            assert!(s.parse::<Money>().is_err(), "{s:?}");
+        }
+        assert_eq!(
+            "1.005".parse::<Money>().unwrap_err().to_string(),
+            "'1.005' is not a valid amount, use at most two decimals"
+        );
+    }
+
+    #[test]
+    fn money_display_round_trips() {
+        for paise in [
+            0,
+            1,
+            5,
+            99,
+            100,
+            10_050,
+            10_000_000,
+            -5,
+            -10_050,
+            i64::MAX,
+            i64::MIN + 1,
+        ] {
+            let money = Money::from_minor(paise);
+            assert_eq!(money.to_string().parse(), Ok(money), "{paise}");
+        }
+        assert_eq!(Money::from_minor(10_000_000).to_string(), "100000");
+        assert_eq!(Money::from_minor(10_000_050).to_string(), "100000.50");
+        assert_eq!(Money::from_minor(-5).to_string(), "-0.05");
+    }
+
+    #[test]
+    fn money_groups_digits_the_indian_way() {
+        assert_eq!(Money::from_major(500).to_indian_string(), "500");
+        assert_eq!(Money::from_major(500_000).to_indian_string(), "5,00,000");
+        assert_eq!(
+            Money::from_minor(123_456_750).to_indian_string(),
+            "12,34,567.50"
+        );
+        assert_eq!(Money::from_major(-1_500).to_indian_string(), "-1,500");
+    }
+
+    #[test]
+    fn money_arithmetic_saturates() {
+        let max = Money::from_minor(i64::MAX);
+        let min = Money::from_minor(i64::MIN);
+
+        assert_eq!(max + Money::from_minor(1), max);
+        assert_eq!(min - Money::from_minor(1), min);
+        assert_eq!(
+            [max, max, Money::from_major(-1)].into_iter().sum::<Money>(),
+            max - Money::from_major(1)
+        );
+        assert_eq!(-min, max);
+        assert_eq!(min.abs(), max);
+
+        let mut total = max;
+        total += max;
+        assert_eq!(total, max);
+        total -= max;
+        assert_eq!(total, Money::ZERO);
+    }
+
+    #[test]
+    fn money_formats_its_bounds() {
+        assert_eq!(
+            Money::from_minor(i64::MIN).to_indian_string(),
+            "-92,23,37,20,36,85,47,758.08"
+        );
+        assert_eq!(
+            Money::from_minor(i64::MIN).to_string(),
+            "-92233720368547758.08"
+        );
+    }
+
+    #[test]
+    fn rate_parses_percentages() {
+        assert_eq!("7.25".parse(), Ok(Rate::from_bps(725)));
+        assert_eq!("7.25%".parse(), Ok(Rate::from_bps(725)));
+        assert_eq!("7".parse(), Ok(Rate::from_bps(700)));
+        assert_eq!("-0.5".parse(), Ok(Rate::from_bps(-50)));
+    }
+
+    #[test]
+    fn rate_rejects_invalid_percentages() {
+        for s in ["", "%", "7.255", "seven", "21474837", "1,5"] {
+            assert!(s.parse::<Rate>().is_err(), "{s:?}");
+        }
+        assert_eq!("21474836.47".parse(), Ok(Rate::from_bps(i32::MAX)));
+    }
+
+    #[test]
+    fn rate_display_round_trips() {
+        for bps in [0, 1, 5, 50, 700, 725, 750, -50, -725, i32::MAX, i32::MIN] {
+            let rate = Rate::from_bps(bps);
+            assert_eq!(rate.to_string().parse(), Ok(rate), "{bps}");
+        }
+        assert_eq!(Rate::from_bps(700).to_string(), "7");
+        assert_eq!(Rate::from_bps(750).to_string(), "7.5");
+        assert_eq!(Rate::from_bps(725).to_string(), "7.25");
+        assert_eq!(Rate::from_bps(5).to_string(), "0.05");
+    }
+}
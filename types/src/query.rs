@@ -0,0 +1,208 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{InvestmentStatus, InvestmentType, ReturnType};
+
+pub const DEFAULT_PER_PAGE: u32 = 20;
+pub const MAX_PER_PAGE: u32 = 100;
+
+/// Query parameters of `GET /invs`. Every filter is optional and they are
+/// combined with AND.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct ListQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inv_type: Option<InvestmentType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_type: Option<ReturnType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<InvestmentStatus>,
+    /// Matches holder names containing this text, ignoring case.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_from: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_to: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_from: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_to: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<SortField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<SortOrder>,
+    /// Page number, starting at 1.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub per_page: Option<u32>,
+}
+
+impl ListQuery {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> u32 {
+        self.per_page
+            .unwrap_or(DEFAULT_PER_PAGE)
+            .clamp(1, MAX_PER_PAGE)
+    }
+
+    /// Number of records to skip to get to the current page.
+    pub fn offset(&self) -> u64 {
+        u64::from(self.page() - 1) * u64::from(self.per_page())
+    }
+
+    /// Newest first unless another sort was asked for.
+    pub fn sort(&self) -> SortField {
+        self.sort.unwrap_or(SortField::CreatedAt)
+    }
+
+    pub fn order(&self) -> SortOrder {
+        self.order.unwrap_or(match self.sort {
+            None => SortOrder::Desc,
+            Some(_) => SortOrder::Asc,
+        })
+    }
+
+    /// The same query sorted by `field`, flipping the order if it was already
+    /// sorted by it, and back on the first page.
+    pub fn sorted_by(&self, field: SortField) -> ListQuery {
+        let order = if self.sort == Some(field) {
+            self.order().reverse()
+        } else {
+            SortOrder::Asc
+        };
+
+        ListQuery {
+            sort: Some(field),
+            order: Some(order),
+            page: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn with_page(&self, page: u32) -> ListQuery {
+        ListQuery {
+            page: Some(page),
+            ..self.clone()
+        }
+    }
+}
+
+/// Columns `GET /invs` can be sorted by.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortField {
+    StartDate,
+    EndDate,
+    InvName,
+    Name,
+    InvType,
+    ReturnType,
+    ReturnRate,
+    InvAmount,
+    ReturnAmount,
+    CreatedAt,
+}
+
+impl SortField {
+    /// Name of the field in the database, which is also its serialized name.
+    pub fn column(&self) -> &'static str {
+        match self {
+            SortField::StartDate => "start_date",
+            SortField::EndDate => "end_date",
+            SortField::InvName => "inv_name",
+            SortField::Name => "name",
+            SortField::InvType => "inv_type",
+            SortField::ReturnType => "return_type",
+            SortField::ReturnRate => "return_rate",
+            SortField::InvAmount => "inv_amount",
+            SortField::ReturnAmount => "return_amount",
+            SortField::CreatedAt => "created_at",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn reverse(self) -> SortOrder {
+        match self {
+            SortOrder::Asc => SortOrder::Desc,
+            SortOrder::Desc => SortOrder::Asc,
+        }
+    }
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortOrder::Asc => f.write_str("ASC"),
+            SortOrder::Desc => f.write_str("DESC"),
+        }
+    }
+}
+
+/// One page of a list, along with how many records match in total.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+impl<T> Page<T> {
+    pub fn page_count(&self) -> u32 {
+        self.total.div_ceil(u64::from(self.per_page.max(1))) as u32
+    }
+}
+
+/// File formats `GET /invs/export` can write.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [ExportFormat::Csv, ExportFormat::Json, ExportFormat::Xlsx];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+}
+
+/// Query parameters of `GET /invs/export` besides the filters and sort of
+/// [`ListQuery`], which it takes too.
+#[derive(Clone, Copy, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
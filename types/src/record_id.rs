@@ -0,0 +1,99 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Id of a database record, like `investment:abc`.
+///
+/// It is sent over the wire as that string, and also reads the
+/// `{ "tb": "investment", "id": { "String": "abc" } }` form SurrealDB gives
+/// its record ids, so that it can be used on both sides of the API without
+/// depending on SurrealDB.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RecordId {
+    /// Table of the record.
+    pub tb: String,
+    /// Key of the record in its table.
+    pub id: String,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseRecordIdError(String);
+
+impl RecordId {
+    pub fn new(tb: impl Into<String>, id: impl Into<String>) -> Self {
+        RecordId {
+            tb: tb.into(),
+            id: id.into(),
+        }
+    }
+}
+
+impl fmt::Display for RecordId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.tb, self.id)
+    }
+}
+
+impl FromStr for RecordId {
+    type Err = ParseRecordIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((tb, id)) if !tb.is_empty() && !id.is_empty() => Ok(RecordId::new(tb, id)),
+            _ => Err(ParseRecordIdError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for ParseRecordIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' is not a record id like table:key", self.0)
+    }
+}
+
+impl std::error::Error for ParseRecordIdError {}
+
+impl Serialize for RecordId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The forms a record id is accepted in.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WireRecordId {
+    Text(String),
+    Thing { tb: String, id: WireKey },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WireKey {
+    Text(String),
+    Number(i64),
+    Tagged(TaggedKey),
+}
+
+/// Key of a SurrealDB `Thing`, tagged with its kind.
+#[derive(Deserialize)]
+enum TaggedKey {
+    String(String),
+    Number(i64),
+}
+
+impl<'de> Deserialize<'de> for RecordId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match WireRecordId::deserialize(deserializer)? {
+            WireRecordId::Text(text) => text.parse().map_err(serde::de::Error::custom),
+            WireRecordId::Thing { tb, id } => {
+                let id = match id {
+                    WireKey::Text(id) | WireKey::Tagged(TaggedKey::String(id)) => id,
+                    WireKey::Number(id) | WireKey::Tagged(TaggedKey::Number(id)) => id.to_string(),
+                };
+                Ok(RecordId { tb, id })
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{Money, RecordId};
+
+/// Days ahead of the end date of an investment that reminders are sent,
+/// unless configured otherwise.
+pub const DEFAULT_REMINDER_DAYS: [u32; 3] = [30, 7, 1];
+
+/// Notice that an investment is about to mature. One is recorded for each
+/// reminder window an investment enters, and delivered once.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct Reminder {
+    pub id: Option<RecordId>,
+    pub investment: RecordId,
+    pub inv_name: String,
+    pub name: String,
+    pub return_amount: Money,
+    pub end_date: DateTime<Utc>,
+    /// The window the reminder is for, e.g. 7 for the one sent a week ahead.
+    pub days_before: u32,
+    pub created_at: DateTime<Utc>,
+    /// When the reminder was delivered; undelivered ones are retried.
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl Reminder {
+    /// The window whose reminder is due for an investment ending on
+    /// `end_date`: the narrowest of `windows` it has entered by `now`. An
+    /// investment that is further off than every window, or has already
+    /// ended, has none.
+    pub fn due_window(windows: &[u32], end_date: DateTime<Utc>, now: DateTime<Utc>) -> Option<u32> {
+        let days_left = (end_date.date_naive() - now.date_naive()).num_days();
+        if days_left < 0 {
+            return None;
+        }
+
+        windows
+            .iter()
+            .copied()
+            .filter(|&window| i64::from(window) >= days_left)
+            .min()
+    }
+
+    /// Key of the reminder of an investment for a window. Moving the end
+    /// date makes for new reminders, so the new date is announced too.
+    pub fn key(investment: &RecordId, end_date: DateTime<Utc>, days_before: u32) -> String {
+        format!(
+            "{}_{}_{days_before}",
+            investment.id,
+            end_date.format("%Y%m%d")
+        )
+    }
+
+    /// Days left until the end date as of `now`.
+    pub fn days_left(&self, now: DateTime<Utc>) -> i64 {
+        (self.end_date.date_naive() - now.date_naive()).num_days()
+    }
+
+    pub fn subject(&self) -> String {
+        format!(
+            "{} of {} matures on {}",
+            self.inv_name,
+            self.name,
+            self.end_date.format("%d-%m-%Y")
+        )
+    }
+
+    pub fn message(&self, now: DateTime<Utc>) -> String {
+        let when = match self.days_left(now) {
+            ..=0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            days => format!("in {days} days"),
+        };
+
+        format!(
+            "{} of {} matures {when}, on {}, returning ₹{}.",
+            self.inv_name,
+            self.name,
+            self.end_date.format("%d-%m-%Y"),
+            self.return_amount.to_indian_string()
+        )
+    }
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{InvestmentStatus, Money};
+
+/// Totals of the investments that share a status.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct StatusTotals {
+    pub status: InvestmentStatus,
+    pub count: u64,
+    pub invested: Money,
+    pub expected_return: Money,
+    /// Interest of the investments that have already reached their end date.
+    pub interest_earned: Money,
+}
+
+/// Totals of the whole portfolio, served by `GET /invs/summary`.
+#[derive(Clone, PartialEq, Debug, Default, Deserialize, Serialize)]
+pub struct PortfolioSummary {
+    /// Amount currently invested. Renewed, closed and withdrawn investments
+    /// are left out, as their money has been paid out or moved into the
+    /// investments they were renewed into.
+    pub total_invested: Money,
+    /// Return amount of the investments counted in `total_invested`.
+    pub total_expected_return: Money,
+    /// Interest of every investment that has reached its end date.
+    pub total_interest_earned: Money,
+    pub by_status: Vec<StatusTotals>,
+}
+
+impl PortfolioSummary {
+    /// Builds the summary from per-status totals, merging any repeated status.
+    pub fn from_totals(totals: impl IntoIterator<Item = StatusTotals>) -> Self {
+        let mut summary = PortfolioSummary::default();
+
+        for totals in totals {
+            if !totals.status.is_final() {
+                summary.total_invested += totals.invested;
+                summary.total_expected_return += totals.expected_return;
+            }
+            summary.total_interest_earned += totals.interest_earned;
+
+            match summary
+                .by_status
+                .iter_mut()
+                .find(|existing| existing.status == totals.status)
+            {
+                Some(existing) => {
+                    existing.count += totals.count;
+                    existing.invested += totals.invested;
+                    existing.expected_return += totals.expected_return;
+                    existing.interest_earned += totals.interest_earned;
+                }
+                None => summary.by_status.push(totals),
+            }
+        }
+
+        summary.by_status.sort_by_key(|totals| {
+            InvestmentStatus::ALL
+                .iter()
+                .position(|status| *status == totals.status)
+        });
+        summary
+    }
+
+    /// Number of investments with the given status.
+    pub fn count(&self, status: InvestmentStatus) -> u64 {
+        self.by_status
+            .iter()
+            .find(|totals| totals.status == status)
+            .map_or(0, |totals| totals.count)
+    }
+}
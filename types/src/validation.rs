@@ -0,0 +1,363 @@
+//! Rules an investment has to follow, shared by the form and the API.
+
+use chrono::Months;
+
+use crate::{FieldErrors, Investment, Money};
+
+/// Longest holder name accepted, in characters.
+pub const MAX_NAME_LEN: usize = 64;
+
+/// Largest amount accepted, ₹1,000 crore, so that totals of many investments
+/// stay far from the bounds of [`Money`].
+pub const MAX_AMOUNT: Money = Money::from_major(10_000_000_000);
+
+/// Longest term accepted, in years, well past the 10 years banks offer
+/// deposits for.
+pub const MAX_TERM_YEARS: u32 = 20;
+
+/// Checks `investment` against every rule, returning the messages of the ones
+/// it breaks keyed by the id of the form field they are about.
+pub fn validate(investment: &Investment) -> Result<(), FieldErrors> {
+    let mut errors = FieldErrors::new();
+    let mut error = |field: &str, message: &str| {
+        errors.insert(field.to_string(), message.to_string());
+    };
+
+    if investment.inv_name.trim().is_empty() {
+        error("inv-name", "Investment Name can not be blank");
+    }
+
+    if investment.name.trim().is_empty() {
+        error("name", "Name can not be blank");
+    } else if investment.name.chars().count() > MAX_NAME_LEN {
+        error(
+            "name",
+            &format!("Name can not be longer than {MAX_NAME_LEN} characters"),
+        );
+    }
+
+    if investment.inv_amount.is_zero() {
+        error("inv-amount", "Investment Amount can not be blank");
+    } else if !investment.inv_amount.is_positive() {
+        error("inv-amount", "Investment Amount must be more than 0");
+    } else if investment.inv_amount > MAX_AMOUNT {
+        error(
+            "inv-amount",
+            &format!(
+                "Investment Amount can not be more than {}",
+                MAX_AMOUNT.to_indian_string()
+            ),
+        );
+    }
+
+    if investment.return_amount.is_zero() {
+        error("return-amount", "Return Amount can not be blank");
+    } else if !investment.return_amount.is_positive() {
+        error("return-amount", "Return Amount must be more than 0");
+    } else if investment.return_amount > MAX_AMOUNT {
+        error(
+            "return-amount",
+            &format!(
+                "Return Amount can not be more than {}",
+                MAX_AMOUNT.to_indian_string()
+            ),
+        );
+    }
+
+    if investment.inv_amount > investment.return_amount {
+        error(
+            "inv-amount",
+            "Investment Amount can not be more than Return Amount",
+        );
+        error(
+            "return-amount",
+            "Return Amount can not be less than Investment Amount",
+        );
+    }
+
+    if investment.return_rate.is_zero() {
+        error("return-rate", "Return Rate can not be blank");
+    } else if !investment.return_rate.is_positive() {
+        error("return-rate", "Return Rate must be more than 0");
+    }
+
+    match (investment.start_date, investment.end_date) {
+        (Some(start_date), Some(end_date)) if end_date <= start_date => {
+            error("end-date", "End Date must be after Start Date");
+        }
+        (Some(start_date), Some(end_date))
+            if start_date
+                .checked_add_months(Months::new(MAX_TERM_YEARS * 12))
+                .is_some_and(|latest| end_date > latest) =>
+        {
+            error(
+                "end-date",
+                &format!("End Date can not be more than {MAX_TERM_YEARS} years after Start Date"),
+            );
+        }
+        (start_date, end_date) => {
+            if start_date.is_none() {
+                error("start-date", "Start Date can not be blank");
+            }
+            if end_date.is_none() {
+                error("end-date", "End Date can not be blank");
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{InvestmentType, Money, Rate, ReturnType};
+
+    fn valid() -> Investment {
+        Investment {
+            inv_name: "Bank".to_string(),
+            inv_type: InvestmentType::Fd,
+            return_rate: Rate::from_bps(700),
+            return_type: ReturnType::Ordinary,
+            inv_amount: Money::from_major(100_000),
+            return_amount: Money::from_major(107_000),
+            name: "Asha".to_string(),
+            start_date: Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()),
+            end_date: Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+            ..Investment::default()
+        }
+    }
+
+    /// The errors of `investment`, as `(field, message)` pairs.
+    fn errors(investment: &Investment) -> Vec<(String, String)> {
+        let mut errors: Vec<_> = validate(investment)
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        errors.sort();
+        errors
+    }
+
+    fn error(field: &str, message: &str) -> (String, String) {
+        (field.to_string(), message.to_string())
+    }
+
+    #[test]
+    fn valid_investment_passes() {
+        assert_eq!(validate(&valid()), Ok(()));
+    }
+
+    #[test]
+    fn names_can_not_be_blank() {
+        let investment = Investment {
+            inv_name: " ".to_string(),
+            name: String::new(),
+            ..valid()
+        };
+        assert_eq!(
+            errors(&investment),
+            [
+                error("inv-name", "Investment Name can not be blank"),
+                error("name", "Name can not be blank"),
+            ]
+        );
+    }
+
+    #[test]
+    fn name_is_at_most_max_name_len_characters() {
+        // counted in characters, not bytes
+        let name = |len: usize| Investment {
+            name: "é".repeat(len),
+            ..valid()
+        };
+        assert_eq!(validate(&name(MAX_NAME_LEN)), Ok(()));
+        assert_eq!(
+            errors(&name(MAX_NAME_LEN + 1)),
+            [error("name", "Name can not be longer than 64 characters")]
+        );
+    }
+
+    #[test]
+    fn amounts_must_be_positive() {
+        let zero = Investment {
+            inv_amount: Money::ZERO,
+            return_amount: Money::ZERO,
+            ..valid()
+        };
+        assert_eq!(
+            errors(&zero),
+            [
+                error("inv-amount", "Investment Amount can not be blank"),
+                error("return-amount", "Return Amount can not be blank"),
+            ]
+        );
+
+        let negative = Investment {
+            inv_amount: Money::from_major(-2),
+            return_amount: Money::from_major(-1),
+            ..valid()
+        };
+        assert_eq!(
+            errors(&negative),
+            [
+                error("inv-amount", "Investment Amount must be more than 0"),
+                error("return-amount", "Return Amount must be more than 0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn amounts_are_at_most_max_amount() {
+        let at_most = Investment {
+            inv_amount: MAX_AMOUNT,
+            return_amount: MAX_AMOUNT,
+            ..valid()
+        };
+        assert_eq!(validate(&at_most), Ok(()));
+
+        let over = Investment {
+            inv_amount: MAX_AMOUNT + Money::from_minor(1),
+            return_amount: Money::from_minor(i64::MAX),
+            ..valid()
+        };
+        assert_eq!(
+            errors(&over),
+            [
+                error(
+                    "inv-amount",
+                    "Investment Amount can not be more than 10,00,00,00,000"
+                ),
+                error(
+                    "return-amount",
+                    "Return Amount can not be more than 10,00,00,00,000"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn return_amount_can_not_be_less_than_the_investment() {
+        let investment = Investment {
+            return_amount: Money::from_minor(Money::from_major(100_000).minor() - 1),
+            ..valid()
+        };
+        assert_eq!(
+            errors(&investment),
+            [
+                error(
+                    "inv-amount",
+                    "Investment Amount can not be more than Return Amount"
+                ),
+                error(
+                    "return-amount",
+                    "Return Amount can not be less than Investment Amount"
+                ),
+            ]
+        );
+
+        let equal = Investment {
+            return_amount: Money::from_major(100_000),
+            ..valid()
+        };
+        assert_eq!(validate(&equal), Ok(()));
+    }
+
+    #[test]
+    fn return_rate_must_be_positive() {
+        let rate = |bps: i32| Investment {
+            return_rate: Rate::from_bps(bps),
+            ..valid()
+        };
+        assert_eq!(
+            errors(&rate(0)),
+            [error("return-rate", "Return Rate can not be blank")]
+        );
+        assert_eq!(
+            errors(&rate(-1)),
+            [error("return-rate", "Return Rate must be more than 0")]
+        );
+        assert_eq!(validate(&rate(1)), Ok(()));
+    }
+
+    #[test]
+    fn end_date_must_be_after_start_date() {
+        let same_day = Investment {
+            end_date: valid().start_date,
+            ..valid()
+        };
+        assert_eq!(
+            errors(&same_day),
+            [error("end-date", "End Date must be after Start Date")]
+        );
+
+        let before = Investment {
+            start_date: valid().end_date,
+            end_date: valid().start_date,
+            ..valid()
+        };
+        assert_eq!(
+            errors(&before),
+            [error("end-date", "End Date must be after Start Date")]
+        );
+    }
+
+    #[test]
+    fn term_is_at_most_max_term_years() {
+        let end = |year: i32, day: u32| Investment {
+            end_date: Some(Utc.with_ymd_and_hms(year, 1, day, 0, 0, 0).unwrap()),
+            ..valid()
+        };
+        assert_eq!(validate(&end(2044, 1)), Ok(()));
+        assert_eq!(
+            errors(&end(2044, 2)),
+            [error(
+                "end-date",
+                "End Date can not be more than 20 years after Start Date"
+            )]
+        );
+
+        let far = Investment {
+            end_date: Some(chrono::DateTime::<Utc>::MAX_UTC),
+            ..valid()
+        };
+        assert_eq!(
+            errors(&far),
+            [error(
+                "end-date",
+                "End Date can not be more than 20 years after Start Date"
+            )]
+        );
+    }
+
+    #[test]
+    fn dates_can_not_be_missing() {
+        let no_start = Investment {
+            start_date: None,
+            ..valid()
+        };
+        assert_eq!(
+            errors(&no_start),
+            [error("start-date", "Start Date can not be blank")]
+        );
+
+        let no_dates = Investment {
+            start_date: None,
+            end_date: None,
+            ..valid()
+        };
+        assert_eq!(
+            errors(&no_dates),
+            [
+                error("end-date", "End Date can not be blank"),
+                error("start-date", "Start Date can not be blank"),
+            ]
+        );
+    }
+}
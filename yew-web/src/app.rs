@@ -1,53 +1,182 @@
 use std::rc::Rc;
 
-use surrealdb::sql::Thing;
+use gloo_dialogs::alert;
 use yew::{function_component, html, use_effect_with, use_reducer, Callback, Html};
-
-use crate::components::{inv_list::InvestmentList, switcher::DarkModeContent};
-use crate::{controllers::*, state::InvestmentState};
-use types::Investment;
+use yew_router::prelude::{use_location, use_navigator, use_route, BrowserRouter, Link};
+
+use crate::components::{
+    dashboard::Dashboard, export_links::ExportLinks, import_inv::ImportInvestments,
+    inv_list::InvestmentList, inv_page::InvestmentPage, maturity_calendar::MaturityCalendar,
+    settings_page::SettingsPage, switcher::DarkModeContent, undo_toast::UndoToast,
+};
+use crate::routes::Route;
+use crate::settings::Settings;
+use crate::{controllers::*, state::*};
+use types::{BulkOperation, ImportRequest, Investment, ListQuery, RecordId};
 
 #[function_component(App)]
 pub fn app() -> Html {
+    html! {
+        <BrowserRouter>
+            <Pages />
+        </BrowserRouter>
+    }
+}
+
+/// The pages of the app around the state they share, so that the list keeps
+/// its page and filters while another page is shown.
+#[function_component(Pages)]
+fn pages() -> Html {
     let investments = use_reducer(InvestmentState::default);
     let investment_controller = Rc::new(InvestmentController::new(investments.clone()));
-
-    // Get all investments on app startup
+    let route = use_route::<Route>().unwrap_or(Route::NotFound);
+    let location = use_location();
+    let navigator = use_navigator();
+
+    // The query of the list is kept in the URL, so that going back and forth
+    // in the history of the browser goes through the pages and filters seen
+    let list_query = (route == Route::Investments).then(|| {
+        let query = location
+            .and_then(|location| location.query::<ListQuery>().ok())
+            .unwrap_or_default();
+        Settings::load().apply(query)
+    });
+
+    // Load the investments whenever the list is shown or its query changes
     {
         let investment_controller = investment_controller.clone();
 
-        use_effect_with((), move |_| {
-            investment_controller.init_investments();
-            || {} // return empty destructor closure (cleanup use_effect)
-        }); // only call on first render
+        use_effect_with(list_query, move |query| {
+            if let Some(query) = query {
+                investment_controller.load_investments(query.clone());
+            }
+        });
     }
 
+    let on_query = Callback::from(move |query: ListQuery| {
+        if let Some(navigator) = &navigator {
+            if let Err(e) = navigator.push_with_query(&Route::Investments, &query) {
+                alert(&e.to_string());
+            }
+        }
+    });
+
     let on_create_investment = {
         let investment_controller = investment_controller.clone();
 
-        Callback::from(move |inv: Investment| investment_controller.create_investment(inv))
+        Callback::from(move |(inv, responder): (Investment, Responder)| {
+            investment_controller.create_investment(inv, responder)
+        })
     };
 
     let on_delete_investment = {
         let investment_controller = investment_controller.clone();
 
-        Callback::from(move |id: Thing| investment_controller.delete_investment(id))
+        Callback::from(move |id: RecordId| investment_controller.delete_investment(id))
+    };
+
+    let on_undo_delete = {
+        let investment_controller = investment_controller.clone();
+
+        Callback::from(move |id: RecordId| investment_controller.restore_investment(id))
+    };
+
+    let on_dismiss_undo = {
+        let investments = investments.clone();
+
+        Callback::from(move |id: RecordId| investments.dispatch(InvestmentAction::DismissUndo(id)))
+    };
+
+    let on_bulk = {
+        let investment_controller = investment_controller.clone();
+
+        Callback::from(
+            move |(operations, responder): (Vec<BulkOperation>, Responder)| {
+                investment_controller.bulk_investments(operations, responder)
+            },
+        )
+    };
+
+    let on_import = {
+        let investment_controller = investment_controller.clone();
+
+        Callback::from(
+            move |(request, responder): (ImportRequest, ImportResponder)| {
+                investment_controller.import_investments(request, responder)
+            },
+        )
     };
 
     let on_edit_investment = {
         let investment_controller = investment_controller.clone();
 
-        Callback::from(move |inv: Investment| investment_controller.edit_investment(inv))
+        Callback::from(move |(inv, responder): (Investment, Responder)| {
+            investment_controller.edit_investment(inv, responder)
+        })
     };
 
-    let on_renew_investment = {
+    let on_reload_investment = {
         let investment_controller = investment_controller.clone();
 
-        Callback::from(move |(old_inv, renew_inv): (Investment, Investment)| {
-            investment_controller.renew_investment(old_inv, renew_inv)
+        Callback::from(move |(id, responder): (RecordId, InvestmentResponder)| {
+            investment_controller.reload_investment(id, responder)
         })
     };
 
+    let on_renew_investment = {
+        let investment_controller = investment_controller.clone();
+
+        Callback::from(
+            move |(old_inv, renew_inv, responder): (Investment, Investment, Responder)| {
+                investment_controller.renew_investment(old_inv, renew_inv, responder)
+            },
+        )
+    };
+
+    let tab = |label: &str, to: Route, query: Option<ListQuery>| {
+        let active = match to {
+            Route::Investments => matches!(route, Route::Investments | Route::Investment { .. }),
+            _ => route == to,
+        };
+        let classes = if active {
+            "text-3xl font-black text-text-950"
+        } else {
+            "text-3xl font-black text-text-500 hover:text-text-950"
+        };
+        html! {
+            <Link<Route, ListQuery> {to} {query} {classes}>{label.to_string()}</Link<Route, ListQuery>>
+        }
+    };
+
+    let page = match &route {
+        Route::Investments => html! {
+            <>
+                <div class="mb-4">
+                    <ImportInvestments on_import={on_import} />
+                </div>
+                <div class="flex flex-col xl:flex-row gap-4">
+                    <div class="flex-1 min-w-0">
+                        <InvestmentList investments={investments.investments.clone()} query={investments.query.clone()} total={investments.total} on_query={on_query.clone()} summary={investments.summary.clone()} create_investment={on_create_investment} delete_investment={on_delete_investment} edit_investment={on_edit_investment.clone()} reload_investment={on_reload_investment.clone()} renew_investment={on_renew_investment} bulk={on_bulk}/>
+                    </div>
+                    <div class="xl:w-[28rem] xl:mt-5">
+                        <MaturityCalendar changes={investments.changes} query={investments.query.clone()} on_query={on_query} />
+                    </div>
+                </div>
+            </>
+        },
+        Route::Investment { id } => html! {
+            <InvestmentPage id={Route::investment_id(id)} edit_investment={on_edit_investment} reload_investment={on_reload_investment} />
+        },
+        Route::Dashboard => html! { <Dashboard /> },
+        Route::Settings => html! { <SettingsPage /> },
+        Route::NotFound => html! {
+            <div class="flex flex-col gap-2 text-text-950">
+                <p>{"There is no page here."}</p>
+                <Link<Route> to={Route::Investments} classes="hover:underline">{"Back to the investments"}</Link<Route>>
+            </div>
+        },
+    };
+
     html! {
         <div class="flex flex-col mt-14 mx-auto gap-6">
             <header class="flex flex-col mx-auto w-full text-text-50">
@@ -60,17 +189,30 @@ pub fn app() -> Html {
                     <div class="absolute top-3 left-1/2 w-[30vw] h-[30vw] bg-primary-600 mix-blend-hard-light rounded-full filter blur-xl md:blur-2xl lg:blur-3xl opacity-70 animate-blob animation-delay-3000 pointer-events-none"></div>
                     <div class="absolute top-3 right-0 w-[30vw] h-[30vw] bg-accent-600 mix-blend-hard-light rounded-full filter blur-xl md:blur-2xl lg:blur-3xl opacity-70 animate-blob2 pointer-events-none"></div>
                     <div class="flex">
-                        <h1 class="text-3xl font-black text-text-950">{"Investments"}</h1>
-                        <div class="ml-auto flex items-center">
+                        <nav class="flex gap-6">
+                            { tab("Investments", Route::Investments, Some(investments.query.clone())) }
+                            { tab("Dashboard", Route::Dashboard, None) }
+                            { tab("Settings", Route::Settings, None) }
+                        </nav>
+                        <div class="ml-auto flex items-center gap-4">
+                            if route == Route::Investments {
+                                <ExportLinks query={investments.query.clone()} />
+                            }
                             <DarkModeContent />
                         </div>
                     </div>
                     <hr class="mb-6 border-t-2" />
-                    <div>
-                        <InvestmentList investments={investments.investments.clone()} create_investment={on_create_investment} delete_investment={on_delete_investment} edit_investment={on_edit_investment} renew_investment={on_renew_investment}/>
-                    </div>
+                    { page }
                 </div>
             </main>
+            {
+                match &investments.deleted {
+                    Some(Investment { id: Some(id), inv_name, .. }) => html! {
+                        <UndoToast id={id.clone()} inv_name={inv_name.clone()} on_undo={on_undo_delete} on_dismiss={on_dismiss_undo} />
+                    },
+                    _ => html! {},
+                }
+            }
             <footer class="mt-3 mb-6">
             </footer>
         </div>
@@ -1,15 +1,31 @@
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
 
 use chrono::{DateTime, Utc};
-use yew::{html, Callback, Event, Html, InputEvent, Properties};
+use gloo_dialogs::alert;
+use yew::{html, Callback, Event, Html, InputEvent, MouseEvent, Properties};
 
-use types::Investment;
+use types::{validation, ApiError, Investment};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct BaseFormComponent {
     pub error_messages: HashMap<String, String>,
+    /// Whether the return amount was computed by the form, so that it follows
+    /// the fields it is computed from. One typed in or loaded from the API is
+    /// kept until it is calculated again on request.
+    pub auto_return_amount: bool,
 }
 
+/// Fields the return amount is computed from.
+const CALCULATION_FIELDS: [&str; 5] = [
+    "inv-type",
+    "return-type",
+    "compounding",
+    "return-rate",
+    "inv-amount",
+];
+
 impl BaseFormComponent {
     pub fn input_field(
         &self,
@@ -24,6 +40,7 @@ impl BaseFormComponent {
                 <label for={field_id_string.clone()} class="block mb-2 text-sm font-medium">{self.kebab_to_title(field_id)}</label>
                 <input
                     type={field_type.to_string()}
+                    step={(field_type == "number").then_some("0.01")}
                     value={field_value.to_string()}
                     oninput={on_input}
                     id={field_id_string.clone()}
@@ -34,6 +51,33 @@ impl BaseFormComponent {
         }
     }
 
+    /// The return amount input, with a button to replace it with the computed
+    /// one.
+    pub fn return_amount_field(
+        &self,
+        field_value: &str,
+        on_input: Callback<InputEvent>,
+        on_calculate: Callback<MouseEvent>,
+    ) -> Html {
+        html! {
+            <div>
+                <div class="flex items-baseline justify-between mb-2">
+                    <label for="return-amount" class="block text-sm font-medium">{"Return Amount"}</label>
+                    <button type="button" onclick={on_calculate} class="text-xs font-medium text-accent-600 hover:underline">{"Calculate"}</button>
+                </div>
+                <input
+                    type="number"
+                    step="0.01"
+                    value={field_value.to_string()}
+                    oninput={on_input}
+                    id="return-amount"
+                    class="border border-background-300 text-text-950 text-sm rounded-lg block w-full p-2.5 bg-background-50 placeholder-text-400"
+                />
+                { self.error("return-amount") }
+            </div>
+        }
+    }
+
     pub fn select_field(
         &self,
         field_id: &str,
@@ -59,6 +103,19 @@ impl BaseFormComponent {
         }
     }
 
+    /// Renders one `<option>` per variant of an enum, selecting the current one.
+    pub fn enum_options<T: Display + PartialEq>(&self, variants: &[T], selected: &T) -> Html {
+        variants
+            .iter()
+            .map(|variant| {
+                let value = variant.to_string();
+                html! {
+                    <option value={value.clone()} selected={variant == selected}>{value}</option>
+                }
+            })
+            .collect::<Html>()
+    }
+
     pub fn date_field(
         &self,
         field_id: &str,
@@ -88,30 +145,68 @@ impl BaseFormComponent {
             "name" => {
                 investment.name = value;
             }
-            "inv-type" => {
-                investment.inv_type = value;
-            }
-            "return-type" => {
-                investment.return_type = value;
+            "institution" => {
+                investment.institution = value;
             }
-            "return-rate" => {
-                investment.return_rate = value.parse().unwrap_or(0);
-            }
-            "inv-amount" => {
-                investment.inv_amount = value.parse().unwrap_or(0);
-                if investment.inv_amount < investment.return_amount {
-                    self.error_messages.remove("return-amount");
+            "inv-type" => match value.parse() {
+                Ok(inv_type) => investment.inv_type = inv_type,
+                Err(e) => {
+                    self.error_messages.insert(field.to_string(), e.to_string());
+                    return;
                 }
-            }
-            "return-amount" => {
-                investment.return_amount = value.parse().unwrap_or(0);
-                if investment.inv_amount < investment.return_amount {
-                    self.error_messages.remove("inv-amount");
+            },
+            "return-type" => match value.parse() {
+                Ok(return_type) => investment.return_type = return_type,
+                Err(e) => {
+                    self.error_messages.insert(field.to_string(), e.to_string());
+                    return;
                 }
-            }
+            },
+            "compounding" => match value.parse() {
+                Ok(compounding) => investment.compounding = Some(compounding),
+                Err(e) => {
+                    self.error_messages.insert(field.to_string(), e.to_string());
+                    return;
+                }
+            },
+            "return-rate" => match parse_or_default(&value) {
+                Ok(rate) => investment.return_rate = rate,
+                Err(e) => {
+                    self.error_messages.insert(field.to_string(), e.to_string());
+                    return;
+                }
+            },
+            "inv-amount" => match parse_or_default(&value) {
+                Ok(amount) => {
+                    investment.inv_amount = amount;
+                    if investment.inv_amount < investment.return_amount {
+                        self.error_messages.remove("return-amount");
+                    }
+                }
+                Err(e) => {
+                    self.error_messages.insert(field.to_string(), e.to_string());
+                    return;
+                }
+            },
+            "return-amount" => match parse_or_default(&value) {
+                Ok(amount) => {
+                    investment.return_amount = amount;
+                    self.auto_return_amount = false;
+                    if investment.inv_amount < investment.return_amount {
+                        self.error_messages.remove("inv-amount");
+                    }
+                }
+                Err(e) => {
+                    self.error_messages.insert(field.to_string(), e.to_string());
+                    return;
+                }
+            },
             _ => {}
         }
         self.error_messages.remove(field);
+        if CALCULATION_FIELDS.contains(&field) {
+            self.refill_return_amount(investment);
+        }
     }
 
     pub fn update_date_field(
@@ -130,94 +225,54 @@ impl BaseFormComponent {
             _ => {}
         }
         self.error_messages.remove(field);
+        self.refill_return_amount(investment);
     }
 
-    pub fn validate_form(&mut self, investment: &mut Investment) -> bool {
-        let mut is_valid = true;
-
-        if investment.inv_name.is_empty() {
-            self.error_messages.insert(
-                "inv-name".to_string(),
-                "Investment Name can not be blank".to_string(),
-            );
-            is_valid = false;
-        }
-
-        if investment.name.is_empty() {
-            self.error_messages
-                .insert("name".to_string(), "Name can not be blank".to_string());
-            is_valid = false;
-        }
-
-        if investment.inv_type.is_empty() {
-            self.error_messages.insert(
-                "inv-type".to_string(),
-                "Investment Type can not be blank".to_string(),
-            );
-            is_valid = false;
-        }
-
-        if investment.return_type.is_empty() {
-            self.error_messages.insert(
-                "return-type".to_string(),
-                "Return Type can not be blank".to_string(),
-            );
-            is_valid = false;
-        }
-
-        if investment.inv_amount == 0 {
-            self.error_messages.insert(
-                "inv-amount".to_string(),
-                "Investment Amount can not be blank".to_string(),
-            );
-            is_valid = false;
-        }
-
-        if investment.return_amount == 0 {
-            self.error_messages.insert(
-                "return-amount".to_string(),
-                "Return Amount can not be blank".to_string(),
-            );
-            is_valid = false;
-        }
+    /// Replaces the return amount with the computed one, which then follows
+    /// the fields it is computed from again.
+    pub fn calculate_return_amount(&mut self, investment: &mut Investment) {
+        self.auto_return_amount = true;
+        self.fill_return_amount(investment);
+    }
 
-        if investment.inv_amount > investment.return_amount {
-            self.error_messages.insert(
-                "inv-amount".to_string(),
-                "Investment Amount can not be more than Return Amount".to_string(),
-            );
-            self.error_messages.insert(
-                "return-amount".to_string(),
-                "Return Amount can not be less than Investment Amount".to_string(),
-            );
-            is_valid = false;
+    /// Computes the return amount again after one of the fields it is computed
+    /// from changed, unless it was typed in or loaded from the API.
+    fn refill_return_amount(&mut self, investment: &mut Investment) {
+        if self.auto_return_amount {
+            self.fill_return_amount(investment);
         }
+    }
 
-        if investment.return_rate == 0 {
-            self.error_messages.insert(
-                "return-rate".to_string(),
-                "Return Rate can not be blank".to_string(),
-            );
-            is_valid = false;
+    /// Fills in the return amount computed from the other fields, once they
+    /// are complete enough to compute it. It can still be overwritten by hand.
+    fn fill_return_amount(&mut self, investment: &mut Investment) {
+        if let Some(maturity) = investment.maturity() {
+            investment.return_amount = maturity.maturity_value;
+            self.error_messages.remove("return-amount");
+            self.error_messages.remove("inv-amount");
         }
+    }
 
-        if investment.start_date.is_none() {
-            self.error_messages.insert(
-                "start-date".to_string(),
-                "Start Date can not be blank".to_string(),
-            );
-            is_valid = false;
+    /// Checks the investment with the same rules as the API, showing the
+    /// errors next to their fields.
+    pub fn validate_form(&mut self, investment: &mut Investment) -> bool {
+        match validation::validate(investment) {
+            Ok(()) => true,
+            Err(errors) => {
+                self.error_messages.extend(errors);
+                false
+            }
         }
+    }
 
-        if investment.end_date.is_none() {
-            self.error_messages.insert(
-                "end-date".to_string(),
-                "End Date can not be blank".to_string(),
-            );
-            is_valid = false;
+    /// Shows an error returned by the API: next to the fields it is about, or
+    /// in an alert if it is not about any field.
+    pub fn show_api_error(&mut self, error: ApiError) {
+        if error.fields.is_empty() {
+            alert(&error.to_string());
+        } else {
+            self.error_messages.extend(error.fields);
         }
-
-        is_valid
     }
 
     pub fn error(&self, field_id: &str) -> Html {
@@ -248,5 +303,14 @@ impl BaseFormComponent {
     }
 }
 
+/// Parses a numeric field, treating an empty input as zero.
+fn parse_or_default<T: FromStr + Default>(value: &str) -> Result<T, T::Err> {
+    if value.trim().is_empty() {
+        Ok(T::default())
+    } else {
+        value.parse()
+    }
+}
+
 // TODO: use this https://gist.github.com/intendednull/85de78d965e728c0a5b675c64adbf3f7
 // to make this more generic and add validation via inbuild methods
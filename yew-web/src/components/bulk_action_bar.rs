@@ -0,0 +1,80 @@
+use gloo_dialogs::confirm;
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, html, use_state, Callback, Html, InputEvent, Properties, TargetCast,
+};
+
+use types::InvestmentStatus;
+
+/// Statuses the selected investments can be moved to, with their button labels.
+const STATUS_ACTIONS: [(InvestmentStatus, &str); 2] = [
+    (InvestmentStatus::Closed, "Close"),
+    (InvestmentStatus::Withdrawn, "Withdraw"),
+];
+
+#[derive(Properties, PartialEq)]
+pub struct BulkActionBarProps {
+    /// Number of selected investments.
+    pub count: usize,
+    pub on_set_status: Callback<InvestmentStatus>,
+    pub on_delete: Callback<()>,
+    pub on_add_tag: Callback<String>,
+    pub on_clear: Callback<()>,
+}
+
+/// Actions applied to every selected investment at once.
+#[function_component(BulkActionBar)]
+pub fn bulk_action_bar(props: &BulkActionBarProps) -> Html {
+    let tag = use_state(String::new);
+    let count = props.count;
+
+    let on_set_status = |status: InvestmentStatus, label: &'static str| {
+        let on_set_status = props.on_set_status.clone();
+        Callback::from(move |_| {
+            if confirm(&format!("{label} {count} investments?")) {
+                on_set_status.emit(status);
+            }
+        })
+    };
+    let on_delete = {
+        let on_delete = props.on_delete.clone();
+        Callback::from(move |_| {
+            if confirm(&format!("Delete {count} investments?")) {
+                on_delete.emit(());
+            }
+        })
+    };
+    let on_tag_input = {
+        let tag = tag.clone();
+        Callback::from(move |e: InputEvent| {
+            tag.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let on_add_tag = {
+        let on_add_tag = props.on_add_tag.clone();
+        let tag = tag.clone();
+        Callback::from(move |_| {
+            if !tag.trim().is_empty() {
+                on_add_tag.emit(tag.trim().to_string());
+                tag.set(String::new());
+            }
+        })
+    };
+    let on_clear = props.on_clear.reform(|_| ());
+
+    html! {
+        <div class="flex flex-wrap items-center gap-3 px-4 py-3 bg-primary-50 text-text-950 text-sm">
+            <span class="font-semibold">{format!("{count} selected")}</span>
+            { for STATUS_ACTIONS.into_iter().map(|(status, label)| html! {
+                <button type="button" onclick={on_set_status(status, label)} class="px-3 py-1.5 rounded bg-secondary-600 text-text-50 hover:bg-secondary-700">{label}</button>
+            }) }
+            <button type="button" onclick={on_delete} class="px-3 py-1.5 rounded bg-red-500 text-text-50 hover:bg-red-600">{"Delete"}</button>
+            <div class="flex items-center gap-1">
+                <input type="text" placeholder="Tag" value={(*tag).clone()} oninput={on_tag_input}
+                    class="px-2 py-1.5 rounded border border-background-300 bg-background-50" />
+                <button type="button" onclick={on_add_tag} class="px-3 py-1.5 rounded bg-accent-600 text-text-50 hover:bg-accent-700">{"Add tag"}</button>
+            </div>
+            <button type="button" onclick={on_clear} class="ml-auto hover:underline">{"Clear selection"}</button>
+        </div>
+    }
+}
@@ -0,0 +1,151 @@
+use yew::{function_component, html, AttrValue, Html, Properties};
+
+/// Colours of the slices of a chart, as SVG strokes and matching legend swatches.
+const PALETTE: [(&str, &str); 8] = [
+    ("stroke-primary-600", "bg-primary-600"),
+    ("stroke-secondary-500", "bg-secondary-500"),
+    ("stroke-accent-600", "bg-accent-600"),
+    ("stroke-amber-400", "bg-amber-400"),
+    ("stroke-green-500", "bg-green-500"),
+    ("stroke-sky-500", "bg-sky-500"),
+    ("stroke-purple-500", "bg-purple-500"),
+    ("stroke-red-400", "bg-red-400"),
+];
+
+/// Radius of a circle 100 long, so that lengths along it are percentages.
+const RADIUS: f64 = 15.915_494_309_189_533;
+
+/// One value of a chart.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Datum {
+    pub label: String,
+    pub value: f64,
+    /// The value as shown in the legend and tooltips.
+    pub display: String,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ChartProps {
+    pub title: AttrValue,
+    pub data: Vec<Datum>,
+}
+
+/// Share of each value in the total, as a ring with a legend.
+#[function_component(DonutChart)]
+pub fn donut_chart(props: &ChartProps) -> Html {
+    let total: f64 = props.data.iter().map(|datum| datum.value.max(0.0)).sum();
+
+    let mut offset = 0.0;
+    let slices = props
+        .data
+        .iter()
+        .zip(PALETTE.iter().cycle())
+        .map(|(datum, (stroke, _))| {
+            let share = if total > 0.0 {
+                datum.value.max(0.0) / total * 100.0
+            } else {
+                0.0
+            };
+            // slices start at the top and go clockwise
+            let dash_offset = 25.0 - offset;
+            offset += share;
+            html! {
+                <circle cx="21" cy="21" r={RADIUS.to_string()} fill="transparent" stroke-width="6" class={*stroke}
+                    stroke-dasharray={format!("{share} {}", 100.0 - share)} stroke-dashoffset={dash_offset.to_string()}>
+                    <title>{format!("{}: {}", datum.label, datum.display)}</title>
+                </circle>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <figure class="flex flex-col gap-3 p-4 rounded-lg bg-background-50 text-text-950 text-sm">
+            <figcaption class="font-semibold">{props.title.clone()}</figcaption>
+            if props.data.is_empty() {
+                <p class="text-text-500">{"Nothing invested yet."}</p>
+            } else {
+                <div class="flex items-center gap-4">
+                    <svg viewBox="0 0 42 42" class="w-32 h-32 shrink-0" role="img" aria-label={props.title.clone()}>
+                        <circle cx="21" cy="21" r={RADIUS.to_string()} fill="transparent" stroke-width="6" class="stroke-background-200" />
+                        { slices }
+                    </svg>
+                    <ul class="flex flex-col gap-1 min-w-0">
+                        { for props.data.iter().zip(PALETTE.iter().cycle()).map(|(datum, (_, swatch))| {
+                            let share = if total > 0.0 { datum.value.max(0.0) / total * 100.0 } else { 0.0 };
+                            html! {
+                                <li class="flex items-center gap-2">
+                                    <span class={format!("w-3 h-3 rounded-sm shrink-0 {swatch}")}></span>
+                                    <span class="truncate">{&datum.label}</span>
+                                    <span class="ml-auto pl-2 text-text-500 whitespace-nowrap">{format!("{} · {share:.0}%", datum.display)}</span>
+                                </li>
+                            }
+                        }) }
+                    </ul>
+                </div>
+            }
+        </figure>
+    }
+}
+
+/// Width of the bar chart in SVG units; the chart scales to its container.
+const BAR_CHART_WIDTH: f64 = 480.0;
+const BAR_CHART_HEIGHT: f64 = 160.0;
+/// Room below the bars for their labels.
+const LABEL_HEIGHT: f64 = 16.0;
+
+/// One bar per value, labelled every `label_every` bars.
+#[derive(Properties, PartialEq)]
+pub struct BarChartProps {
+    pub title: AttrValue,
+    pub data: Vec<Datum>,
+    #[prop_or(1)]
+    pub label_every: usize,
+}
+
+#[function_component(BarChart)]
+pub fn bar_chart(props: &BarChartProps) -> Html {
+    let max = props
+        .data
+        .iter()
+        .map(|datum| datum.value)
+        .fold(0.0, f64::max);
+    let slot = BAR_CHART_WIDTH / props.data.len().max(1) as f64;
+    let plot_height = BAR_CHART_HEIGHT - LABEL_HEIGHT;
+
+    let bars = props
+        .data
+        .iter()
+        .enumerate()
+        .map(|(index, datum)| {
+            let height = if max > 0.0 {
+                datum.value.max(0.0) / max * plot_height
+            } else {
+                0.0
+            };
+            let x = index as f64 * slot;
+            html! {
+                <g>
+                    <rect x={(x + slot * 0.15).to_string()} y={(plot_height - height).to_string()}
+                        width={(slot * 0.7).to_string()} height={height.to_string()} rx="1" class="fill-primary-600">
+                        <title>{format!("{}: {}", datum.label, datum.display)}</title>
+                    </rect>
+                    if index % props.label_every.max(1) == 0 {
+                        <text x={(x + slot / 2.0).to_string()} y={(BAR_CHART_HEIGHT - 4.0).to_string()} text-anchor="middle" font-size="9" class="fill-text-500">
+                            {&datum.label}
+                        </text>
+                    }
+                </g>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <figure class="flex flex-col gap-3 p-4 rounded-lg bg-background-50 text-text-950 text-sm">
+            <figcaption class="font-semibold">{props.title.clone()}</figcaption>
+            <svg viewBox={format!("0 0 {BAR_CHART_WIDTH} {BAR_CHART_HEIGHT}")} class="w-full" role="img" aria-label={props.title.clone()}>
+                <line x1="0" y1={plot_height.to_string()} x2={BAR_CHART_WIDTH.to_string()} y2={plot_height.to_string()} stroke-width="0.5" class="stroke-background-300" />
+                { bars }
+            </svg>
+        </figure>
+    }
+}
@@ -7,7 +7,8 @@ use yew::events::{Event, InputEvent};
 use yew::{html, Callback, Component, Html, Properties, SubmitEvent};
 
 use super::base_inv_form::BaseFormComponent;
-use types::Investment;
+use crate::controllers::Responder;
+use types::{ApiError, Compounding, Investment, InvestmentType, ReturnType};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct CreateInvForm {
@@ -18,14 +19,17 @@ pub struct CreateInvForm {
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct CreateInvFormProps {
-    pub create_investment: Callback<Investment>,
+    pub create_investment: Callback<(Investment, Responder)>,
 }
 
 pub enum Form {
     Update(String, String),
     UpdateDate(String, Option<DateTime<Utc>>),
+    /// Replaces the return amount with the computed one.
+    Calculate,
     Reset,
     Save,
+    Response(Result<(), ApiError>),
 }
 
 impl Component for CreateInvForm {
@@ -34,31 +38,18 @@ impl Component for CreateInvForm {
 
     fn create(ctx: &yew::Context<Self>) -> Self {
         Self {
-            state: Investment {
-                id: None,
-                inv_name: "".to_string(),
-                name: "".to_string(),
-                inv_type: "".to_string(),
-                return_type: "".to_string(),
-                inv_amount: 0,
-                return_amount: 0,
-                return_rate: 0,
-                inv_status: None,
-                start_date: None,
-                end_date: None,
-                created_at: None,
-                updated_at: None,
-            },
+            state: Investment::default(),
             props: CreateInvFormProps {
                 create_investment: ctx.props().create_investment.clone(),
             },
             base: BaseFormComponent {
                 error_messages: HashMap::new(),
+                auto_return_amount: true,
             },
         }
     }
 
-    fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Form::Update(field, value) => {
                 self.base.update_field(&mut self.state, &field, value);
@@ -66,13 +57,20 @@ impl Component for CreateInvForm {
             Form::UpdateDate(field, date) => {
                 self.base.update_date_field(&mut self.state, &field, date);
             }
+            Form::Calculate => {
+                self.base.calculate_return_amount(&mut self.state);
+            }
             Form::Reset => {
                 self.reset_form();
             }
             Form::Save => {
-                if self.save_form() {
-                    self.reset_form();
-                }
+                self.save_form(ctx);
+            }
+            Form::Response(Ok(())) => {
+                self.reset_form();
+            }
+            Form::Response(Err(error)) => {
+                self.base.show_api_error(error);
             }
         }
         true
@@ -85,23 +83,17 @@ impl Component for CreateInvForm {
                     { self.date_field(ctx, "end-date", &self.state.end_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()) }
                     { self.input_field(ctx, "inv-name", "text", &self.state.inv_name) }
                     { self.input_field(ctx, "name", "text", &self.state.name) }
-                    { self.select_field(ctx, "inv-type", &self.state.inv_type,
-                        html! {
-                            <>
-                                <option value="FD">{"FD"}</option>
-                                <option value="RD">{"RD"}</option>
-                            </>
-                        }
+                    { self.input_field(ctx, "institution", "text", &self.state.institution) }
+                    { self.select_field(ctx, "inv-type", &self.state.inv_type.to_string(),
+                        self.base.enum_options(&InvestmentType::ALL, &self.state.inv_type)
                     ) }
-                    { self.select_field(ctx, "return-type", &self.state.return_type,
-                        html! {
-                            <>
-                                <option value="Ordinary">{"Ordinary"}</option>
-                                <option value="Culmulative">{"Culmulative"}</option>
-                            </>
-                        }
+                    { self.select_field(ctx, "return-type", &self.state.return_type.to_string(),
+                        self.base.enum_options(&ReturnType::ALL, &self.state.return_type)
                     ) }
-                    { self.input_field(ctx, "return-amount", "number", &self.state.return_amount.to_string()) }
+                    { self.select_field(ctx, "compounding", &self.state.compounding().to_string(),
+                        self.base.enum_options(&Compounding::ALL, &self.state.compounding())
+                    ) }
+                    { self.return_amount_field(ctx, &self.state.return_amount.to_string()) }
                     { self.input_field(ctx, "inv-amount", "number", &self.state.inv_amount.to_string()) }
                     { self.input_field(ctx, "return-rate", "number", &self.state.return_rate.to_string()) }
                     <button type="button" onclick={ctx.link().callback(|_| Form::Reset)} class="inline-flex justify-center items-center px-5 py-2.5 mt-3 sm:mt-5 text-sm font-medium text-center text-text-950 bg-background-50 hover:bg-background-100 rounded-lg ring-2 ring-primary-600 ring-inset focus:ring-4 focus:ring-primary-200">{"Reset"}</button>
@@ -129,6 +121,18 @@ impl CreateInvForm {
             .input_field(field_id, field_type, field_value, on_input)
     }
 
+    fn return_amount_field(&self, ctx: &yew::Context<Self>, field_value: &str) -> Html {
+        let on_input = ctx.link().callback(|e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+            Form::Update("return-amount".to_string(), input.value())
+        });
+        self.base.return_amount_field(
+            field_value,
+            on_input,
+            ctx.link().callback(|_| Form::Calculate),
+        )
+    }
+
     fn select_field(
         &self,
         ctx: &yew::Context<Self>,
@@ -164,12 +168,15 @@ impl CreateInvForm {
         self.base.date_field(field_id, field_value, on_input)
     }
 
-    fn save_form(&mut self) -> bool {
+    fn save_form(&mut self, ctx: &yew::Context<Self>) -> bool {
         // Validate form fields
         let is_valid = self.base.validate_form(&mut self.state);
 
         if is_valid {
-            self.props.create_investment.emit(self.state.clone());
+            // the form is reset once the investment has been created
+            self.props
+                .create_investment
+                .emit((self.state.clone(), ctx.link().callback(Form::Response)));
             true
         } else {
             // If the form is not valid, return false
@@ -178,14 +185,7 @@ impl CreateInvForm {
     }
 
     fn reset_form(&mut self) {
-        self.state.inv_name = "".to_string();
-        self.state.name = "".to_string();
-        self.state.inv_type = "".to_string();
-        self.state.return_type = "".to_string();
-        self.state.inv_amount = 0;
-        self.state.return_amount = 0;
-        self.state.return_rate = 0;
-        self.state.start_date = None;
-        self.state.end_date = None;
+        self.state = Investment::default();
+        self.base.auto_return_amount = true;
     }
 }
@@ -0,0 +1,84 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with, use_state, Html};
+
+use super::charts::{BarChart, Datum, DonutChart};
+use crate::inv_api::fetch_analytics;
+use types::{Allocation, ApiError, Money, PortfolioAnalytics, FORECAST_MONTHS};
+
+/// Amounts as plotted, in rupees.
+fn rupees(money: Money) -> f64 {
+    money.minor() as f64 / 100.0
+}
+
+fn allocation_data(allocations: &[Allocation]) -> Vec<Datum> {
+    allocations
+        .iter()
+        .map(|allocation| Datum {
+            label: allocation.label.clone(),
+            value: rupees(allocation.invested),
+            display: format!("₹{}", allocation.invested.to_indian_string()),
+        })
+        .collect()
+}
+
+/// Charts of where the money is invested and the interest it is expected to
+/// earn, as computed by the API.
+#[function_component(Dashboard)]
+pub fn dashboard() -> Html {
+    let analytics = use_state(|| None::<Result<PortfolioAnalytics, ApiError>>);
+
+    {
+        let analytics = analytics.clone();
+        use_effect_with((), move |_| {
+            spawn_local(async move {
+                analytics.set(Some(fetch_analytics().await));
+            });
+        });
+    }
+
+    let analytics = match &*analytics {
+        None => return html! { <p class="text-text-500">{"Loading analytics…"}</p> },
+        Some(Err(e)) => return html! { <p class="text-red-600">{e.to_string()}</p> },
+        Some(Ok(analytics)) => analytics,
+    };
+
+    let forecast_total: Money = analytics
+        .monthly_interest
+        .iter()
+        .map(|month| month.interest)
+        .sum();
+    let monthly_interest: Vec<Datum> = analytics
+        .monthly_interest
+        .iter()
+        .map(|month| Datum {
+            label: month.month.format("%b %y").to_string(),
+            value: rupees(month.interest),
+            display: format!("₹{}", month.interest.to_indian_string()),
+        })
+        .collect();
+
+    let card = |label: &str, value: String| {
+        html! {
+            <div class="flex flex-col gap-1 p-4 rounded-lg bg-background-50 text-text-950">
+                <span class="text-sm text-text-500">{label}</span>
+                <span class="text-2xl font-semibold">{value}</span>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="flex flex-col gap-4">
+            <div class="grid gap-4 md:grid-cols-3">
+                { card("Invested", format!("₹{}", analytics.total_invested.to_indian_string())) }
+                { card("Weighted average return rate", format!("{}%", analytics.weighted_return_rate)) }
+                { card(&format!("Interest over the next {FORECAST_MONTHS} months"), format!("₹{}", forecast_total.to_indian_string())) }
+            </div>
+            <div class="grid gap-4 lg:grid-cols-3">
+                <DonutChart title="By investment type" data={allocation_data(&analytics.by_type)} />
+                <DonutChart title="By institution" data={allocation_data(&analytics.by_institution)} />
+                <DonutChart title="By holder" data={allocation_data(&analytics.by_holder)} />
+            </div>
+            <BarChart title="Expected interest per month" data={monthly_interest} label_every={3} />
+        </div>
+    }
+}
@@ -7,19 +7,23 @@ use yew::events::{Event, InputEvent};
 use yew::{html, Callback, Component, Html, Properties};
 
 use super::base_inv_form::BaseFormComponent;
-use types::Investment;
+use crate::controllers::{InvestmentResponder, Responder};
+use types::{ApiError, Compounding, Investment, InvestmentType, RecordId, ReturnType};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct EditInvForm {
     form_changed: bool,
     show_edit_confirmation: bool,
+    /// Set when someone else changed the investment while it was being edited.
+    conflict: Option<String>,
     props: EditInvFormProps,
     base: BaseFormComponent,
 }
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct EditInvFormProps {
-    pub edit_investment: Callback<Investment>,
+    pub edit_investment: Callback<(Investment, Responder)>,
+    pub reload_investment: Callback<(RecordId, InvestmentResponder)>,
     pub investment: Investment,
     pub on_edit: Callback<()>,
 }
@@ -27,9 +31,15 @@ pub struct EditInvFormProps {
 pub enum Form {
     Update(String, String),
     UpdateDate(String, Option<DateTime<Utc>>),
+    /// Replaces the return amount with the computed one.
+    Calculate,
     Confirm,
     Cancel,
     Edit,
+    Response(Result<(), ApiError>),
+    /// Discards the changes and loads the latest version of the investment.
+    Reload,
+    Reloaded(Result<Box<Investment>, ApiError>),
 }
 
 impl Component for EditInvForm {
@@ -40,18 +50,21 @@ impl Component for EditInvForm {
         Self {
             form_changed: false,
             show_edit_confirmation: false,
+            conflict: None,
             props: EditInvFormProps {
                 edit_investment: ctx.props().edit_investment.clone(),
+                reload_investment: ctx.props().reload_investment.clone(),
                 investment: ctx.props().investment.clone(),
                 on_edit: ctx.props().on_edit.clone(),
             },
             base: BaseFormComponent {
                 error_messages: HashMap::new(),
+                auto_return_amount: false,
             },
         }
     }
 
-    fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Form::Update(field, value) => {
                 self.base
@@ -63,11 +76,47 @@ impl Component for EditInvForm {
                     .update_date_field(&mut self.props.investment, &field, date);
                 self.form_changed = true;
             }
+            Form::Calculate => {
+                self.base
+                    .calculate_return_amount(&mut self.props.investment);
+                self.form_changed = true;
+            }
             Form::Confirm => {
-                if self.save_form() {
-                    self.props.on_edit.emit(());
+                if !self.save_form(ctx) {
+                    self.show_edit_confirmation = false;
                 }
             }
+            Form::Response(Ok(())) => {
+                self.props.on_edit.emit(());
+            }
+            Form::Response(Err(error)) if error.status == 409 => {
+                self.show_edit_confirmation = false;
+                self.conflict = Some(error.message);
+            }
+            Form::Response(Err(error)) => {
+                self.show_edit_confirmation = false;
+                self.base.show_api_error(error);
+            }
+            Form::Reload => {
+                if let Some(id) = self.props.investment.id.clone() {
+                    let responder =
+                        ctx.link()
+                            .callback(|response: Result<Investment, ApiError>| {
+                                Form::Reloaded(response.map(Box::new))
+                            });
+                    self.props.reload_investment.emit((id, responder));
+                }
+            }
+            Form::Reloaded(Ok(investment)) => {
+                self.props.investment = *investment;
+                self.form_changed = false;
+                self.conflict = None;
+                self.base.auto_return_amount = false;
+                self.base.error_messages.clear();
+            }
+            Form::Reloaded(Err(error)) => {
+                self.base.show_api_error(error);
+            }
             Form::Cancel => {
                 self.show_edit_confirmation = false;
             }
@@ -80,29 +129,31 @@ impl Component for EditInvForm {
     fn view(&self, ctx: &yew::Context<Self>) -> Html {
         html! {
             <div class="mx-auto w-full relative">
+                {if let Some(conflict) = &self.conflict {
+                    html! {
+                        <div class="flex items-center gap-4 mb-4 p-3 rounded border border-red-500 text-red-600" role="alert">
+                            <p>{conflict}</p>
+                            <button onclick={ctx.link().callback(|_| Form::Reload)} class="ml-auto bg-background-500 px-4 py-2 rounded text-text-50">{"Reload"}</button>
+                        </div>
+                    }
+                } else { html! {} } }
                 <form>
                     <div class="grid gap-6 mb-6 md:grid-cols-2 lg:grid-cols-3 text-text-950">
                         { self.date_field(ctx, "start-date", &self.props.investment.start_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()) }
                         { self.date_field(ctx, "end-date", &self.props.investment.end_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()) }
                         { self.input_field(ctx, "inv-name", "text", &self.props.investment.inv_name) }
                         { self.input_field(ctx, "name", "text", &self.props.investment.name) }
-                        { self.select_field(ctx, "inv-type", &self.props.investment.inv_type,
-                            html! {
-                                <>
-                                    <option value="FD" selected={self.props.investment.inv_type == "FD"}>{"FD"}</option>
-                                    <option value="RD" selected={self.props.investment.inv_type == "RD"}>{"RD"}</option>
-                                </>
-                            }
+                        { self.input_field(ctx, "institution", "text", &self.props.investment.institution) }
+                        { self.select_field(ctx, "inv-type", &self.props.investment.inv_type.to_string(),
+                            self.base.enum_options(&InvestmentType::ALL, &self.props.investment.inv_type)
                         ) }
-                        { self.select_field(ctx, "return-type", &self.props.investment.return_type,
-                            html! {
-                                <>
-                                    <option value="Ordinary" selected={self.props.investment.return_type == "Ordinary"}>{"Ordinary"}</option>
-                                    <option value="Culmulative" selected={self.props.investment.return_type == "Culmulative"}>{"Culmulative"} </option>
-                                </>
-                            }
+                        { self.select_field(ctx, "return-type", &self.props.investment.return_type.to_string(),
+                            self.base.enum_options(&ReturnType::ALL, &self.props.investment.return_type)
                         ) }
-                        { self.input_field(ctx, "return-amount", "number", &self.props.investment.return_amount.to_string()) }
+                        { self.select_field(ctx, "compounding", &self.props.investment.compounding().to_string(),
+                            self.base.enum_options(&Compounding::ALL, &self.props.investment.compounding())
+                        ) }
+                        { self.return_amount_field(ctx, &self.props.investment.return_amount.to_string()) }
                         { self.input_field(ctx, "inv-amount", "number", &self.props.investment.inv_amount.to_string()) }
                         { self.input_field(ctx, "return-rate", "number", &self.props.investment.return_rate.to_string()) }
                         <button type="submit" disabled={!self.form_changed}
@@ -151,6 +202,18 @@ impl EditInvForm {
             .input_field(field_id, field_type, field_value, on_input)
     }
 
+    fn return_amount_field(&self, ctx: &yew::Context<Self>, field_value: &str) -> Html {
+        let on_input = ctx.link().callback(|e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+            Form::Update("return-amount".to_string(), input.value())
+        });
+        self.base.return_amount_field(
+            field_value,
+            on_input,
+            ctx.link().callback(|_| Form::Calculate),
+        )
+    }
+
     fn select_field(
         &self,
         ctx: &yew::Context<Self>,
@@ -186,14 +249,16 @@ impl EditInvForm {
         self.base.date_field(field_id, field_value, on_input)
     }
 
-    fn save_form(&mut self) -> bool {
+    fn save_form(&mut self, ctx: &yew::Context<Self>) -> bool {
         // Validate form fields
         let is_valid = self.base.validate_form(&mut self.props.investment);
 
         if is_valid {
-            self.props
-                .edit_investment
-                .emit(self.props.investment.clone());
+            // the form is closed once the investment has been updated
+            self.props.edit_investment.emit((
+                self.props.investment.clone(),
+                ctx.link().callback(Form::Response),
+            ));
             true
         } else {
             // If the form is not valid, return false
@@ -1,6 +1,7 @@
 use yew::prelude::{html, Callback, Component, Html, Properties};
 
-use types::Investment;
+use crate::controllers::Responder;
+use types::{Investment, InvestmentStatus, PortfolioSummary};
 
 use super::create_inv_form::CreateInvForm;
 
@@ -12,7 +13,8 @@ pub struct ExpandableHeader {
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct ExpandableHeaderProps {
-    pub create_investment: Callback<Investment>,
+    pub summary: Option<PortfolioSummary>,
+    pub create_investment: Callback<(Investment, Responder)>,
 }
 
 pub enum ExpandableHeaderState {
@@ -27,6 +29,7 @@ impl Component for ExpandableHeader {
         Self {
             open: false,
             props: ExpandableHeaderProps {
+                summary: ctx.props().summary.clone(),
                 create_investment: ctx.props().create_investment.clone(),
             },
         }
@@ -42,12 +45,26 @@ impl Component for ExpandableHeader {
     }
 
     fn view(&self, ctx: &yew::Context<Self>) -> Html {
+        // read the summary from the context, it changes after every create/edit/delete
+        let summary = ctx.props().summary.clone().unwrap_or_default();
+        let status_split = InvestmentStatus::ALL
+            .iter()
+            .map(|status| format!("{} {}", summary.count(*status), status))
+            .collect::<Vec<_>>()
+            .join(" · ");
+
         html! {
             <div class="w-full mx-auto">
                 <div class="bg-background-50 rounded">
                     <div class="">
                         <button  class="flex items-center justify-between w-full p-3 font-medium rtl:text-left" onclick={ctx.link().callback(|_| ExpandableHeaderState::Toggle)}>
-                            <span class="flex items-center text-text-950"> {"Total: 5,00,000"}</span>
+                            <div class="flex flex-col items-start text-text-950">
+                                <span class="font-semibold">{format!("Total: ₹{}", summary.total_invested.to_indian_string())}</span>
+                                <span class="text-sm text-text-600">
+                                    {format!("Expected return: ₹{} · Interest earned: ₹{}", summary.total_expected_return.to_indian_string(), summary.total_interest_earned.to_indian_string())}
+                                </span>
+                                <span class="text-xs text-text-500">{status_split}</span>
+                            </div>
                                 <svg class="w-7 text-text-950" fill="currentColor" viewBox="0 0 24 24">
                                 <path d="M2 18H12V20H2V18ZM2 11H22V13H2V11ZM2 4H22V6H2V4ZM18 \
                                 18V15H20V18H23V20H20V23H18V20H15V18H18Z" />
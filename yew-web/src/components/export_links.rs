@@ -0,0 +1,27 @@
+use yew::{function_component, html, Html, Properties};
+
+use crate::inv_api::export_url;
+use types::{ExportFormat, ListQuery};
+
+#[derive(Properties, PartialEq)]
+pub struct ExportLinksProps {
+    /// Filters and sort of the list, which the files follow.
+    pub query: ListQuery,
+}
+
+/// Downloads the investments shown in the list, all pages of them, in each
+/// format the API can export.
+#[function_component(ExportLinks)]
+pub fn export_links(props: &ExportLinksProps) -> Html {
+    html! {
+        <div class="flex items-center gap-2 text-sm text-text-950">
+            <span class="font-medium">{"Download"}</span>
+            { for ExportFormat::ALL.into_iter().map(|format| html! {
+                <a href={export_url(&props.query, format)} download=""
+                    class="px-2 py-1 rounded bg-background-200 hover:bg-background-300 uppercase">
+                    {format.as_str()}
+                </a>
+            }) }
+        </div>
+    }
+}
@@ -0,0 +1,260 @@
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{Event, File, HtmlInputElement};
+use yew::{
+    function_component, html, use_state, Callback, Html, InputEvent, Properties, TargetCast,
+};
+
+use crate::controllers::ImportResponder;
+use types::{ApiError, ImportReport, ImportRequest, ImportRow, IMPORT_FIELDS};
+
+#[derive(Properties, PartialEq)]
+pub struct ImportInvestmentsProps {
+    pub on_import: Callback<(ImportRequest, ImportResponder)>,
+}
+
+/// Uploads a CSV file of investments, previews what would be imported and
+/// what is wrong with each row, and imports it once every row is valid.
+#[function_component(ImportInvestments)]
+pub fn import_inv(props: &ImportInvestmentsProps) -> Html {
+    let open = use_state(|| false);
+    let request = use_state(ImportRequest::default);
+    let file_name = use_state(|| None::<String>);
+    let report = use_state(|| None::<ImportReport>);
+    let error = use_state(|| None::<String>);
+    let busy = use_state(|| false);
+
+    let reset = {
+        let (request, file_name, report, error) = (
+            request.clone(),
+            file_name.clone(),
+            report.clone(),
+            error.clone(),
+        );
+        move || {
+            request.set(ImportRequest::default());
+            file_name.set(None);
+            report.set(None);
+            error.set(None);
+        }
+    };
+
+    let on_toggle = {
+        let open = open.clone();
+        let reset = reset.clone();
+        Callback::from(move |_| {
+            if *open {
+                reset();
+            }
+            open.set(!*open);
+        })
+    };
+
+    let on_file = {
+        let (request, file_name, report, error) = (
+            request.clone(),
+            file_name.clone(),
+            report.clone(),
+            error.clone(),
+        );
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file): Option<File> = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            let mut next = (*request).clone();
+            let (request, file_name, report, error) = (
+                request.clone(),
+                file_name.clone(),
+                report.clone(),
+                error.clone(),
+            );
+            spawn_local(async move {
+                let text = JsFuture::from(file.text()).await.ok();
+                match text.and_then(|text| text.as_string()) {
+                    Some(text) => {
+                        next.csv = text;
+                        request.set(next);
+                        file_name.set(Some(file.name()));
+                        error.set(None);
+                    }
+                    None => error.set(Some(format!("{} can not be read", file.name()))),
+                }
+                report.set(None);
+            });
+        })
+    };
+
+    let on_column = |field: &'static str| {
+        let (request, report) = (request.clone(), report.clone());
+        Callback::from(move |e: InputEvent| {
+            let column = e.target_unchecked_into::<HtmlInputElement>().value();
+            let mut next = (*request).clone();
+            if column.trim().is_empty() {
+                next.mapping.remove(field);
+            } else {
+                next.mapping.insert(field.to_string(), column);
+            }
+            request.set(next);
+            report.set(None);
+        })
+    };
+
+    let on_date_format = {
+        let (request, report) = (request.clone(), report.clone());
+        Callback::from(move |e: InputEvent| {
+            let mut next = (*request).clone();
+            next.date_format = e.target_unchecked_into::<HtmlInputElement>().value();
+            request.set(next);
+            report.set(None);
+        })
+    };
+
+    let send = |dry_run: bool| {
+        let on_import = props.on_import.clone();
+        let (request, report, error, busy) =
+            (request.clone(), report.clone(), error.clone(), busy.clone());
+        let (open, reset) = (open.clone(), reset.clone());
+        Callback::from(move |_| {
+            busy.set(true);
+            let (report, error, busy) = (report.clone(), error.clone(), busy.clone());
+            let (open, reset) = (open.clone(), reset.clone());
+            let responder = Callback::from(move |response: Result<ImportReport, ApiError>| {
+                busy.set(false);
+                match response {
+                    Ok(done) if !done.dry_run && done.imported > 0 => {
+                        reset();
+                        open.set(false);
+                    }
+                    Ok(done) => {
+                        error.set(None);
+                        report.set(Some(done));
+                    }
+                    Err(e) => error.set(Some(e.to_string())),
+                }
+            });
+            on_import.emit((
+                ImportRequest {
+                    dry_run,
+                    ..(*request).clone()
+                },
+                responder,
+            ));
+        })
+    };
+
+    if !*open {
+        return html! {
+            <button type="button" onclick={on_toggle} class="px-3 py-1.5 rounded bg-accent-600 text-text-50 hover:bg-accent-700">{"Import CSV"}</button>
+        };
+    }
+
+    let can_import = matches!(&*report, Some(report) if report.dry_run && !report.has_errors());
+
+    html! {
+        <div class="flex flex-col gap-4 p-4 rounded-lg bg-background-50 text-text-950 text-sm">
+            <div class="flex items-center gap-3">
+                <input type="file" accept=".csv,text/csv" onchange={on_file} />
+                if let Some(name) = &*file_name {
+                    <span class="text-text-500">{name}</span>
+                }
+                <button type="button" onclick={on_toggle} class="ml-auto hover:underline">{"Cancel"}</button>
+            </div>
+            <div class="grid grid-cols-2 md:grid-cols-4 gap-3">
+                { for IMPORT_FIELDS.iter().map(|&(field, required)| html! {
+                    <label class="flex flex-col gap-1">
+                        <span class="font-medium">
+                            {field.replace('_', " ")}
+                            if !required {
+                                <span class="text-text-500">{" (optional)"}</span>
+                            }
+                        </span>
+                        <input type="text" placeholder={field} value={request.mapping.get(field).cloned().unwrap_or_default()} oninput={on_column(field)}
+                            class="px-2 py-1.5 rounded border border-background-300 bg-background-50" />
+                    </label>
+                }) }
+                <label class="flex flex-col gap-1">
+                    <span class="font-medium">{"date format"}</span>
+                    <input type="text" value={request.date_format.clone()} oninput={on_date_format}
+                        class="px-2 py-1.5 rounded border border-background-300 bg-background-50" />
+                </label>
+            </div>
+            <p class="text-text-500">{"Columns are matched by header, the field name unless another one is given. Tags are separated by ';'."}</p>
+            if let Some(error) = &*error {
+                <p class="text-red-600">{error}</p>
+            }
+            <div class="flex gap-3">
+                <button type="button" onclick={send(true)} disabled={request.csv.is_empty() || *busy}
+                    class="px-3 py-1.5 rounded bg-secondary-600 text-text-50 hover:bg-secondary-700 disabled:opacity-50">{"Preview"}</button>
+                if let (true, Some(report)) = (can_import, &*report) {
+                    <button type="button" onclick={send(false)} disabled={*busy}
+                        class="px-3 py-1.5 rounded bg-accent-600 text-text-50 hover:bg-accent-700 disabled:opacity-50">
+                        {format!("Import {} investments", report.rows.len())}
+                    </button>
+                }
+            </div>
+            if let Some(report) = &*report {
+                { preview(report) }
+            }
+        </div>
+    }
+}
+
+fn preview(report: &ImportReport) -> Html {
+    let failed = report
+        .rows
+        .iter()
+        .filter(|row| !row.errors.is_empty())
+        .count();
+
+    html! {
+        <div class="overflow-x-auto">
+            if failed > 0 {
+                <p class="mb-2 text-red-600">
+                    {format!("{failed} of {} rows have errors, nothing will be imported until they are fixed.", report.rows.len())}
+                </p>
+            }
+            <table class="w-full text-left">
+                <thead>
+                    <tr>
+                        { for ["Line", "Name", "Investment Name", "Type", "Amount", "Return", "Rate", "Start Date", "End Date", "Errors"]
+                            .into_iter()
+                            .map(|heading| html! { <th class="px-2 py-1">{heading}</th> }) }
+                    </tr>
+                </thead>
+                <tbody>
+                    { for report.rows.iter().map(preview_row) }
+                </tbody>
+            </table>
+        </div>
+    }
+}
+
+fn preview_row(row: &ImportRow) -> Html {
+    let inv = &row.investment;
+    let date = |date: Option<chrono::DateTime<chrono::Utc>>| {
+        date.map(|date| date.format("%d-%m-%Y").to_string())
+            .unwrap_or_default()
+    };
+    let mut errors: Vec<String> = row
+        .errors
+        .iter()
+        .map(|(field, message)| format!("{field}: {message}"))
+        .collect();
+    errors.sort();
+    let class = if errors.is_empty() { "" } else { "bg-red-50" };
+
+    html! {
+        <tr class={class}>
+            <td class="px-2 py-1">{row.line}</td>
+            <td class="px-2 py-1">{&inv.name}</td>
+            <td class="px-2 py-1">{&inv.inv_name}</td>
+            <td class="px-2 py-1">{format!("{} {}", inv.inv_type.as_str(), inv.return_type.as_str())}</td>
+            <td class="px-2 py-1">{inv.inv_amount.to_indian_string()}</td>
+            <td class="px-2 py-1">{inv.return_amount.to_indian_string()}</td>
+            <td class="px-2 py-1">{format!("{}%", inv.return_rate)}</td>
+            <td class="px-2 py-1">{date(inv.start_date)}</td>
+            <td class="px-2 py-1">{date(inv.end_date)}</td>
+            <td class="px-2 py-1 text-red-600">{errors.join("; ")}</td>
+        </tr>
+    }
+}
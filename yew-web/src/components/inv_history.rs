@@ -0,0 +1,86 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with, use_state, Html, Properties};
+
+use crate::inv_api::fetch_history;
+use types::{ApiError, AuditEntry, FieldChange, Money, Rate, RecordId};
+
+#[derive(Properties, PartialEq)]
+pub struct InvestmentHistoryProps {
+    /// Investment whose changes are shown.
+    pub id: RecordId,
+}
+
+/// Every change made to an investment, newest first, with the fields it
+/// changed and their old and new values.
+#[function_component(InvestmentHistory)]
+pub fn inv_history(props: &InvestmentHistoryProps) -> Html {
+    let history = use_state(|| None::<Result<Vec<AuditEntry>, ApiError>>);
+
+    {
+        let history = history.clone();
+        use_effect_with(props.id.clone(), move |id| {
+            let id = id.clone();
+            spawn_local(async move {
+                history.set(Some(fetch_history(&id).await));
+            });
+        });
+    }
+
+    let entries = match &*history {
+        None => return html! { <p class="text-text-500">{"Loading changes…"}</p> },
+        Some(Err(e)) => return html! { <p class="text-red-600">{e.to_string()}</p> },
+        Some(Ok(entries)) => entries,
+    };
+
+    html! {
+        <details class="mt-4">
+            <summary class="cursor-pointer font-medium">{format!("Changes ({})", entries.len())}</summary>
+            <ul class="mt-2 space-y-3">
+                { for entries.iter().rev().map(history_entry) }
+            </ul>
+        </details>
+    }
+}
+
+fn history_entry(entry: &AuditEntry) -> Html {
+    html! {
+        <li>
+            <p class="text-sm text-text-500">
+                {format!("{} · {} by {}", entry.at.format("%d-%m-%Y %H:%M"), entry.action, entry.actor)}
+            </p>
+            <table class="text-sm">
+                { for entry.changes.iter().map(change_row) }
+            </table>
+        </li>
+    }
+}
+
+fn change_row(change: &FieldChange) -> Html {
+    html! {
+        <tr>
+            <td class="pr-3 text-text-500">{change.field.replace('_', " ")}</td>
+            <td class="pr-3 line-through text-red-600">{show_value(&change.field, &change.from)}</td>
+            <td class="text-green-700">{show_value(&change.field, &change.to)}</td>
+        </tr>
+    }
+}
+
+/// Shows a field the way the rest of the UI does, falling back to its JSON.
+fn show_value(field: &str, value: &Value) -> String {
+    match (field, value) {
+        (_, Value::Null) => "—".to_string(),
+        ("inv_amount" | "return_amount", _) => serde_json::from_value::<Money>(value.clone())
+            .map(Money::to_indian_string)
+            .unwrap_or_else(|_| value.to_string()),
+        ("return_rate", _) => serde_json::from_value::<Rate>(value.clone())
+            .map(|rate| format!("{rate}%"))
+            .unwrap_or_else(|_| value.to_string()),
+        (_, Value::String(s)) => match s.parse::<DateTime<Utc>>() {
+            Ok(date) => date.format("%d-%m-%Y").to_string(),
+            Err(_) => s.clone(),
+        },
+        _ => value.to_string(),
+    }
+}
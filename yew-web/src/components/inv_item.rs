@@ -1,9 +1,14 @@
-use surrealdb::sql::Thing;
-use yew::{html, Callback, Component, Html, Properties};
+use web_sys::HtmlInputElement;
+use yew::{html, Callback, Component, Event, Html, Properties, TargetCast};
+use yew_router::prelude::Link;
 
 use super::edit_inv_form::EditInvForm;
+use super::inv_history::InvestmentHistory;
 use super::renew_inv_form::RenewInvForm;
-use types::Investment;
+use super::renewal_timeline::RenewalTimeline;
+use crate::controllers::{InvestmentResponder, Responder};
+use crate::routes::Route;
+use types::{Investment, RecordId};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct InvestmentItem {
@@ -17,10 +22,13 @@ pub struct InvestmentItem {
 #[derive(Properties, PartialEq, Clone)]
 pub struct InvestmentItemProps {
     pub investment: Investment,
-    pub create_investment: Callback<Investment>,
-    pub delete_investment: Callback<Thing>,
-    pub edit_investment: Callback<Investment>,
-    pub renew_investment: Callback<(Investment, Investment)>,
+    pub selected: bool,
+    pub on_select: Callback<(RecordId, bool)>,
+    pub create_investment: Callback<(Investment, Responder)>,
+    pub delete_investment: Callback<RecordId>,
+    pub edit_investment: Callback<(Investment, Responder)>,
+    pub reload_investment: Callback<(RecordId, InvestmentResponder)>,
+    pub renew_investment: Callback<(Investment, Investment, Responder)>,
 }
 
 pub enum InvestmentItemState {
@@ -44,9 +52,12 @@ impl Component for InvestmentItem {
             show_delete_confirmation: false,
             props: InvestmentItemProps {
                 investment: ctx.props().investment.clone(),
+                selected: ctx.props().selected,
+                on_select: ctx.props().on_select.clone(),
                 create_investment: ctx.props().create_investment.clone(),
                 delete_investment: ctx.props().delete_investment.clone(),
                 edit_investment: ctx.props().edit_investment.clone(),
+                reload_investment: ctx.props().reload_investment.clone(),
                 renew_investment: ctx.props().renew_investment.clone(),
             },
         }
@@ -78,7 +89,7 @@ impl Component for InvestmentItem {
                 let id = match self.props.investment.id.clone() {
                     Some(thing) => thing,
                     None => {
-                        // Handle the None case here. For example, you can return an error or a default Thing.
+                        // Handle the None case here. For example, you can return an error or a default id.
                         return Default::default();
                     }
                 };
@@ -106,6 +117,33 @@ impl Component for InvestmentItem {
             String::new()
         };
 
+        let return_mismatch = match self.props.investment.return_mismatch {
+            Some(difference) => html! {
+                <span class="ml-1 text-yellow-600" title={format!("Differs from the computed return amount by {difference}")}>{"⚠"}</span>
+            },
+            None => html! {},
+        };
+
+        let on_select = {
+            let on_select = self.props.on_select.clone();
+            let id = self.props.investment.id.clone();
+            Callback::from(move |e: Event| {
+                if let Some(id) = id.clone() {
+                    on_select.emit((id, e.target_unchecked_into::<HtmlInputElement>().checked()));
+                }
+            })
+        };
+
+        let tags = self
+            .props
+            .investment
+            .tags
+            .iter()
+            .map(|tag| html! {
+                <span class="mr-1 px-2 py-0.5 rounded-full text-xs font-normal bg-accent-100 text-accent-800">{tag}</span>
+            })
+            .collect::<Html>();
+
         let arrow_down = html! {
             <svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16" fill="currentColor" class="w-4 h-4">
                 <path fill-rule="evenodd" d="M8 15A7 7 0 1 0 8 1a7 7 0 0 0 0 14Zm.75-10.25a.75.75 0 0 0-1.5 0v4.69L6.03 8.22a.75.75 0 0 0-1.06 1.06l2.5 2.5a.75.75 0 0 0 1.06 0l2.5-2.5a.75.75 0 1 0-1.06-1.06L8.75 9.44V4.75Z" clip-rule="evenodd" />
@@ -138,6 +176,10 @@ impl Component for InvestmentItem {
         html! {
                 <>
                     <tr class={format!("{} {}", {if self.open_more || self.open_edit || self.open_renew { "bg-background-50" } else { "border-b dark:border-background-200 hover:bg-background-50" }}, "relative")}>
+                        <td class="px-4 py-4">
+                            <input type="checkbox" aria-label="Select" checked={self.props.selected} onchange={on_select}
+                                class="w-4 h-4 rounded border-background-300" />
+                        </td>
                         <td class="px-6 py-4 min-w-max whitespace-nowrap hidden sm:table-cell">
                             {start_date.clone()}
                             <dl class="lg:hidden">
@@ -147,7 +189,13 @@ impl Component for InvestmentItem {
                         </td>
                         <td class="px-6 py-4 min-w-max whitespace-nowrap hidden lg:table-cell">{end_date.clone()}</td>
                         <th class="px-6 py-4 min-w-max font-medium text-text-950 ">
-                            {&self.props.investment.clone().inv_name}
+                            {match &self.props.investment.id {
+                                Some(id) => html! {
+                                    <Link<Route> to={Route::investment(id)} classes="hover:underline">{self.props.investment.inv_name.clone()}</Link<Route>>
+                                },
+                                None => html! { {&self.props.investment.inv_name} },
+                            }}
+                            <div class="mt-1">{tags}</div>
                             <dl class="font-normal text-text-500">
                                 <dt class="lg:hidden sr-only">{"Name"}</dt>
                                 <dd class="lg:hidden mt-1">{&self.props.investment.clone().name}</dd>
@@ -159,24 +207,25 @@ impl Component for InvestmentItem {
                         </th>
                         <td class="px-6 py-4 min-w-max hidden lg:table-cell">{&self.props.investment.clone().name}</td>
                         <td class="px-6 py-4 min-w-max hidden sm:table-cell">
-                            {&self.props.investment.clone().inv_type}
+                            {self.props.investment.inv_type.to_string()}
                             <dl class="lg:hidden font-normal text-text-500">
                                 <dt class="sr-only">{"Return Type"}</dt>
-                                <dd class="mt-1">{&self.props.investment.clone().return_type}</dd>
+                                <dd class="mt-1">{self.props.investment.return_type.to_string()}</dd>
                                 <dt class="sr-only">{"Return Rate"}</dt>
-                                <dd class="mt-1">{&self.props.investment.clone().return_rate}</dd>
+                                <dd class="mt-1">{format!("{}%", self.props.investment.return_rate)}</dd>
                             </dl>
                         </td>
-                        <td class="px-6 py-4 min-w-max hidden lg:table-cell">{&self.props.investment.clone().return_type}</td>
-                        <td class="px-6 py-4 min-w-max hidden lg:table-cell">{&self.props.investment.clone().return_rate}</td>
-                        <td class="px-6 py-4 min-w-max hidden lg:table-cell">{&self.props.investment.clone().inv_amount} </td>
+                        <td class="px-6 py-4 min-w-max hidden lg:table-cell">{self.props.investment.return_type.to_string()}</td>
+                        <td class="px-6 py-4 min-w-max hidden lg:table-cell">{format!("{}%", self.props.investment.return_rate)}</td>
+                        <td class="px-6 py-4 min-w-max hidden lg:table-cell">{self.props.investment.inv_amount.to_string()} </td>
                         <td class="px-6 py-4 min-w-max font-medium text-text-950">
-                            {&self.props.investment.clone().return_amount}
+                            {self.props.investment.return_amount.to_string()}
+                            {return_mismatch}
                             <dl class="lg:hidden font-normal text-text-500">
                                 <dt class="sr-only">{"Investment"}</dt>
-                                <dd class="mt-1">{&self.props.investment.clone().inv_amount}</dd>
+                                <dd class="mt-1">{self.props.investment.inv_amount.to_string()}</dd>
                                 <dt class="sr-only sm:hidden">{"Investment Type"}</dt>
-                                <dd class="mt-1 sm:hidden">{&self.props.investment.clone().inv_type}</dd>
+                                <dd class="mt-1 sm:hidden">{self.props.investment.inv_type.to_string()}</dd>
                             </dl>
                         </td>
                         <td class="flex flex-col items-start px-6 py-4 whitespace-nowrap">
@@ -221,7 +270,7 @@ impl Component for InvestmentItem {
                                 <td colspan="100%">
                                     <p class="w-full p-4 text-text-950 text-base bg-background-50 rounded-b">
                                         <div class="w-full md:w-auto flex flex-col md:flex-row space-y-2 md:space-y-0 items-stretch md:items-center justify-end md:space-x-3 flex-shrink-0">
-                                            <EditInvForm edit_investment={self.props.edit_investment.clone()} investment={self.props.investment.clone()} on_edit={ctx.link().callback(|_| InvestmentItemState::ToggleExpandEdit)}/>
+                                            <EditInvForm edit_investment={self.props.edit_investment.clone()} reload_investment={self.props.reload_investment.clone()} investment={self.props.investment.clone()} on_edit={ctx.link().callback(|_| InvestmentItemState::ToggleExpandEdit)}/>
                                         </div>
                                     </p>
                                 </td>
@@ -231,9 +280,18 @@ impl Component for InvestmentItem {
                         html! {
                             <tr class="overflow-hidden border-b dark:border-background-200 hover:bg-background-50">
                                 <td colspan="100%">
-                                    <p class="p-4 text-text-950 text-base bg-background-50 rounded-b">
-                                        {self.props.investment.inv_status.as_ref().map_or("No status", |s| &s.status)}
-                                    </p>
+                                    <div class="p-4 text-text-950 text-base bg-background-50 rounded-b">
+                                        <p class="mb-2">{format!("Status: {}", self.props.investment.status())}</p>
+                                        if !self.props.investment.institution.is_empty() {
+                                            <p class="mb-2">{format!("Institution: {}", self.props.investment.institution)}</p>
+                                        }
+                                        {self.props.investment.id.clone().map_or(html! {}, |id| html! {
+                                            <>
+                                                <RenewalTimeline id={id.clone()} />
+                                                <InvestmentHistory {id} />
+                                            </>
+                                        })}
+                                    </div>
                                 </td>
                             </tr>
                         }
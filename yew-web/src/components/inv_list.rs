@@ -1,32 +1,146 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
-use surrealdb::sql::Thing;
+use gloo_dialogs::alert;
 use uuid::Uuid;
-use yew::{function_component, html, Callback, Html, Properties};
+use yew::{function_component, html, use_state, Callback, Html, Properties};
 
+use super::bulk_action_bar::BulkActionBar;
 use super::inv_item::InvestmentItem;
 use crate::components::exp_table_header::ExpandableHeader;
-use types::Investment;
+use crate::controllers::{InvestmentResponder, Responder};
+use types::{
+    ApiError, BulkOperation, Investment, InvestmentStatus, ListQuery, PortfolioSummary, RecordId,
+    SortField, SortOrder,
+};
 
 #[derive(Properties, PartialEq)]
 pub struct InvestmentListProps {
     pub investments: VecDeque<Investment>,
-    pub create_investment: Callback<Investment>,
-    pub delete_investment: Callback<Thing>,
-    pub edit_investment: Callback<Investment>,
-    pub renew_investment: Callback<(Investment, Investment)>,
+    pub query: ListQuery,
+    pub total: u64,
+    pub on_query: Callback<ListQuery>,
+    pub summary: Option<PortfolioSummary>,
+    pub create_investment: Callback<(Investment, Responder)>,
+    pub delete_investment: Callback<RecordId>,
+    pub edit_investment: Callback<(Investment, Responder)>,
+    pub reload_investment: Callback<(RecordId, InvestmentResponder)>,
+    pub renew_investment: Callback<(Investment, Investment, Responder)>,
+    pub bulk: Callback<(Vec<BulkOperation>, Responder)>,
 }
 
 #[function_component(InvestmentList)]
 pub fn investment_list(
     InvestmentListProps {
         investments,
+        query,
+        total,
+        on_query,
+        summary,
         create_investment,
         delete_investment,
         edit_investment,
+        reload_investment,
         renew_investment,
+        bulk,
     }: &InvestmentListProps,
 ) -> Html {
+    let selected = use_state(HashSet::<RecordId>::new);
+
+    // only the investments of the current page can be selected
+    let page_ids: Vec<RecordId> = investments
+        .iter()
+        .filter_map(|investment| investment.id.clone())
+        .collect();
+    let selected_ids: Vec<RecordId> = page_ids
+        .iter()
+        .filter(|id| selected.contains(*id))
+        .cloned()
+        .collect();
+    let all_selected = !page_ids.is_empty() && selected_ids.len() == page_ids.len();
+
+    let on_select = {
+        let selected = selected.clone();
+        Callback::from(move |(id, checked): (RecordId, bool)| {
+            let mut next = (*selected).clone();
+            if checked {
+                next.insert(id);
+            } else {
+                next.remove(&id);
+            }
+            selected.set(next);
+        })
+    };
+    let on_select_all = {
+        let selected = selected.clone();
+        let page_ids = page_ids.clone();
+        Callback::from(move |_| {
+            let next = if all_selected {
+                HashSet::new()
+            } else {
+                page_ids.iter().cloned().collect()
+            };
+            selected.set(next);
+        })
+    };
+
+    // clears the selection once the operations went through
+    let apply_bulk = {
+        let selected = selected.clone();
+        let bulk = bulk.clone();
+        Callback::from(move |operations: Vec<BulkOperation>| {
+            let selected = selected.clone();
+            let responder = Callback::from(move |result: Result<(), ApiError>| match result {
+                Ok(()) => selected.set(HashSet::new()),
+                Err(e) => alert(&e.to_string()),
+            });
+            bulk.emit((operations, responder));
+        })
+    };
+    let bulk_action_bar = if selected_ids.is_empty() {
+        html! {}
+    } else {
+        let on_set_status = {
+            let selected_ids = selected_ids.clone();
+            apply_bulk.reform(move |status: InvestmentStatus| {
+                selected_ids
+                    .iter()
+                    .map(|id| BulkOperation::SetStatus {
+                        id: id.clone(),
+                        status,
+                    })
+                    .collect()
+            })
+        };
+        let on_delete = {
+            let selected_ids = selected_ids.clone();
+            apply_bulk.reform(move |_| {
+                selected_ids
+                    .iter()
+                    .map(|id| BulkOperation::Delete { id: id.clone() })
+                    .collect()
+            })
+        };
+        let on_add_tag = {
+            let selected_ids = selected_ids.clone();
+            apply_bulk.reform(move |tag: String| {
+                selected_ids
+                    .iter()
+                    .map(|id| BulkOperation::AddTags {
+                        id: id.clone(),
+                        tags: vec![tag.clone()],
+                    })
+                    .collect()
+            })
+        };
+        let on_clear = {
+            let selected = selected.clone();
+            Callback::from(move |_| selected.set(HashSet::new()))
+        };
+        html! {
+            <BulkActionBar count={selected_ids.len()} {on_set_status} {on_delete} {on_add_tag} {on_clear} />
+        }
+    };
+
     let investments = investments
     .iter()
     .map(|investment| {
@@ -34,33 +148,87 @@ pub fn investment_list(
         let uuid = Uuid::new_v4();
         let display_string = match investment.id.clone() {
             Some(thing) => format!("{}", thing),
-            None => "No Thing available".to_string(),
+            None => "No id available".to_string(),
         };
         let key = format!("{}-{}", display_string, uuid);
-        html!(<InvestmentItem key={key} investment={investment.clone()} create_investment={create_investment.clone()} delete_investment={delete_investment} edit_investment={edit_investment} renew_investment={renew_investment} />)
+        let is_selected = investment.id.as_ref().is_some_and(|id| selected.contains(id));
+        html!(<InvestmentItem key={key} investment={investment.clone()} selected={is_selected} on_select={on_select.clone()} create_investment={create_investment.clone()} delete_investment={delete_investment} edit_investment={edit_investment} reload_investment={reload_investment} renew_investment={renew_investment} />)
     })
     .collect::<Html>();
 
+    let sort_header = |label: &str, field: SortField, class: &str| {
+        let on_query = on_query.clone();
+        let sorted = query.sorted_by(field);
+        let indicator = match (query.sort == Some(field), query.order()) {
+            (true, SortOrder::Asc) => " ▲",
+            (true, SortOrder::Desc) => " ▼",
+            (false, _) => "",
+        };
+        html! {
+            <th scope="col" class={format!("px-6 py-3 {class}")}>
+                <button type="button" class="uppercase hover:underline" onclick={move |_| on_query.emit(sorted.clone())}>
+                    {format!("{label}{indicator}")}
+                </button>
+            </th>
+        }
+    };
+
+    let page = query.page();
+    let per_page = u64::from(query.per_page());
+    let page_count = total.div_ceil(per_page).max(1) as u32;
+    let first_shown = if *total == 0 { 0 } else { query.offset() + 1 };
+    let last_shown = (query.offset() + per_page).min(*total);
+
+    let page_button = |target: u32, label: String, disabled: bool| {
+        let on_query = on_query.clone();
+        let target_query = query.with_page(target);
+        let class = if target == page && label == target.to_string() {
+            "flex items-center justify-center h-full py-1.5 px-3 leading-tight text-text-50 bg-primary-600 border border-background-300"
+        } else {
+            "flex items-center justify-center h-full py-1.5 px-3 leading-tight text-text-600 bg-background-50 border border-background-300 hover:bg-background-100 disabled:opacity-50"
+        };
+        html! {
+            <li>
+                <button type="button" {class} {disabled} onclick={move |_| on_query.emit(target_query.clone())}>{label}</button>
+            </li>
+        }
+    };
+
+    // show at most five page numbers around the current page
+    let first_page = page
+        .saturating_sub(2)
+        .max(1)
+        .min(page_count.saturating_sub(4).max(1));
+    let last_page = (first_page + 4).min(page_count);
+    let page_buttons = (first_page..=last_page)
+        .map(|target| page_button(target, target.to_string(), false))
+        .collect::<Html>();
+
     html! {
         <section class="p-3 sm:p-5">
             <div class="mx-auto px-4 lg:px-12">
                 <div class="backdrop-blur-sm bg-white/50 dark:bg-black/70 relative shadow-md dark:shadow-white-md rounded-lg overflow-hidden">
                     <div class="flex flex-col md:flex-row items-center justify-between space-y-3 md:space-y-0 md:space-x-4 p-4">
-                        <ExpandableHeader create_investment={create_investment.clone()}/>
+                        <ExpandableHeader summary={summary.clone()} create_investment={create_investment.clone()}/>
                     </div>
+                    {bulk_action_bar}
                     <div class="overflow-x-auto">
                         <table class="w-full text-sm text-left text-text-600">
                             <thead class="text-xs uppercase bg-background-200">
                                 <tr>
-                                    <th scope="col" class="px-6 py-3 hidden sm:table-cell">{"Start Date"}</th>
-                                    <th scope="col" class="px-6 py-3 hidden lg:table-cell">{"End Date"}</th>
-                                    <th scope="col" class="px-6 py-3">{"Investment Name"}</th>
-                                    <th scope="col" class="px-6 py-3 hidden lg:table-cell">{"Name"}</th>
-                                    <th scope="col" class="px-6 py-3 hidden sm:table-cell">{"Investment Type"}</th>
-                                    <th scope="col" class="px-6 py-3 hidden lg:table-cell">{"Return Type"}</th>
-                                    <th scope="col" class="px-6 py-3 hidden lg:table-cell">{"Return Rate"}</th>
-                                    <th scope="col" class="px-6 py-3 hidden lg:table-cell">{"Investment"}</th>
-                                    <th scope="col" class="px-6 py-3">{"Return"}</th>
+                                    <th scope="col" class="px-4 py-3">
+                                        <input type="checkbox" aria-label="Select all" checked={all_selected} onchange={on_select_all}
+                                            class="w-4 h-4 rounded border-background-300" />
+                                    </th>
+                                    { sort_header("Start Date", SortField::StartDate, "hidden sm:table-cell") }
+                                    { sort_header("End Date", SortField::EndDate, "hidden lg:table-cell") }
+                                    { sort_header("Investment Name", SortField::InvName, "") }
+                                    { sort_header("Name", SortField::Name, "hidden lg:table-cell") }
+                                    { sort_header("Investment Type", SortField::InvType, "hidden sm:table-cell") }
+                                    { sort_header("Return Type", SortField::ReturnType, "hidden lg:table-cell") }
+                                    { sort_header("Return Rate", SortField::ReturnRate, "hidden lg:table-cell") }
+                                    { sort_header("Investment", SortField::InvAmount, "hidden lg:table-cell") }
+                                    { sort_header("Return", SortField::ReturnAmount, "") }
                                     <th scope="col" class="px-6 py-3">
                                         <span >{"Actions"}</span>
                                     </th>
@@ -70,6 +238,17 @@ pub fn investment_list(
                         </table>
                     </div>
                     <nav class="flex flex-col md:flex-row justify-between items-start md:items-center space-y-3 md:space-y-0 p-4" aria-label="Table navigation">
+                        <span class="text-sm font-normal text-text-600">
+                            {"Showing "}
+                            <span class="font-semibold text-text-950">{format!("{first_shown}-{last_shown}")}</span>
+                            {" of "}
+                            <span class="font-semibold text-text-950">{total}</span>
+                        </span>
+                        <ul class="inline-flex items-stretch -space-x-px">
+                            { page_button(page.saturating_sub(1).max(1), "Previous".to_string(), page <= 1) }
+                            { page_buttons }
+                            { page_button((page + 1).min(page_count), "Next".to_string(), page >= page_count) }
+                        </ul>
                     </nav>
                 </div>
             </div>
@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with, use_state, Callback, Html, Properties};
+use yew_router::prelude::Link;
+
+use super::edit_inv_form::EditInvForm;
+use super::inv_history::InvestmentHistory;
+use super::renewal_timeline::RenewalTimeline;
+use crate::controllers::{InvestmentResponder, Responder};
+use crate::inv_api::fetch_investment;
+use crate::routes::Route;
+use types::{ApiError, Investment, RecordId};
+
+#[derive(Properties, PartialEq)]
+pub struct InvestmentPageProps {
+    pub id: RecordId,
+    pub edit_investment: Callback<(Investment, Responder)>,
+    pub reload_investment: Callback<(RecordId, InvestmentResponder)>,
+}
+
+/// Everything about one investment, loaded by its id so that its page can be
+/// opened from a link.
+#[function_component(InvestmentPage)]
+pub fn investment_page(props: &InvestmentPageProps) -> Html {
+    let investment = use_state(|| None::<Result<Investment, ApiError>>);
+    let open_edit = use_state(|| false);
+    // bumped to load the investment again once it was edited
+    let version = use_state(|| 0u32);
+
+    {
+        let investment = investment.clone();
+        use_effect_with((props.id.clone(), *version), move |(id, _)| {
+            let id = id.clone();
+            spawn_local(async move {
+                investment.set(Some(fetch_investment(&id).await));
+            });
+        });
+    }
+
+    let investment = match &*investment {
+        None => return html! { <p class="text-text-500">{"Loading investment…"}</p> },
+        Some(Err(e)) => {
+            return html! {
+                <div class="flex flex-col gap-2">
+                    <p class="text-red-600">{e.to_string()}</p>
+                    <Link<Route> to={Route::Investments} classes="hover:underline">{"Back to the investments"}</Link<Route>>
+                </div>
+            }
+        }
+        Some(Ok(investment)) => investment,
+    };
+
+    let on_edit = {
+        let open_edit = open_edit.clone();
+        let version = version.clone();
+        Callback::from(move |_| {
+            open_edit.set(false);
+            version.set(*version + 1);
+        })
+    };
+
+    let toggle_edit = {
+        let open_edit = open_edit.clone();
+        Callback::from(move |_| open_edit.set(!*open_edit))
+    };
+
+    let date = |date: Option<DateTime<Utc>>| {
+        date.map(|date| date.date_naive().format("%d-%m-%Y").to_string())
+            .unwrap_or_default()
+    };
+
+    let field = |label: &str, value: String| {
+        html! {
+            <div>
+                <dt class="text-sm text-text-500">{label}</dt>
+                <dd class="font-medium">{value}</dd>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="flex flex-col gap-4 text-text-950">
+            <Link<Route> to={Route::Investments} classes="text-sm text-text-500 hover:underline">{"◀ Investments"}</Link<Route>>
+            <div class="flex flex-wrap items-baseline gap-3">
+                <h2 class="text-2xl font-semibold">{&investment.inv_name}</h2>
+                <span class="px-2 py-0.5 rounded-full text-xs bg-background-200">{investment.status().to_string()}</span>
+                { for investment.tags.iter().map(|tag| html! {
+                    <span class="px-2 py-0.5 rounded-full text-xs bg-accent-100 text-accent-800">{tag}</span>
+                }) }
+                <button type="button" onclick={toggle_edit} class="ml-auto font-medium text-accent-600 hover:underline">
+                    { if *open_edit { "Close" } else { "Edit" } }
+                </button>
+            </div>
+            if *open_edit {
+                <div class="p-4 rounded-lg bg-background-50">
+                    <EditInvForm edit_investment={props.edit_investment.clone()} reload_investment={props.reload_investment.clone()} investment={investment.clone()} {on_edit} />
+                </div>
+            }
+            <dl class="grid gap-4 p-4 rounded-lg bg-background-50 sm:grid-cols-2 lg:grid-cols-4">
+                { field("Name", investment.name.clone()) }
+                { field("Institution", investment.institution.clone()) }
+                { field("Investment Type", investment.inv_type.to_string()) }
+                { field("Return Type", investment.return_type.to_string()) }
+                { field("Start Date", date(investment.start_date)) }
+                { field("End Date", date(investment.end_date)) }
+                { field("Investment", investment.inv_amount.to_indian_string()) }
+                { field("Return", investment.return_amount.to_indian_string()) }
+                { field("Return Rate", format!("{}%", investment.return_rate)) }
+                { field("Compounding", investment.compounding().to_string()) }
+            </dl>
+            <section class="p-4 rounded-lg bg-background-50">
+                <h3 class="mb-3 font-semibold">{"Renewals"}</h3>
+                <RenewalTimeline id={props.id.clone()} />
+                <InvestmentHistory id={props.id.clone()} />
+            </section>
+        </div>
+    }
+}
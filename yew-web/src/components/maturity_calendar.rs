@@ -0,0 +1,241 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Utc};
+use gloo_dialogs::alert;
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with, use_state, Callback, Html, Properties};
+
+use crate::inv_api::{calendar_url, fetch_every_investment};
+use types::{ApiError, Investment, ListQuery};
+
+/// Most investments listed in a day before the rest are only counted.
+const MAX_SHOWN_PER_DAY: usize = 3;
+
+#[derive(Properties, PartialEq)]
+pub struct MaturityCalendarProps {
+    /// Bumped whenever investments change, to load the month shown again.
+    pub changes: u32,
+    /// Query of the list, which clicking a day filters by end date.
+    pub query: ListQuery,
+    pub on_query: Callback<ListQuery>,
+}
+
+/// How soon an investment is due, which sets the colour of its end date.
+#[derive(Clone, Copy, PartialEq)]
+enum Due {
+    /// Already paid out or renewed, or ended without being so yet.
+    Past,
+    Week,
+    Month,
+    Later,
+}
+
+impl Due {
+    const ALL: [Due; 4] = [Due::Week, Due::Month, Due::Later, Due::Past];
+
+    fn of(investment: &Investment, end_date: NaiveDate, today: NaiveDate) -> Due {
+        let days_left = (end_date - today).num_days();
+        if investment.status().is_final() || days_left < 0 {
+            Due::Past
+        } else if days_left <= 7 {
+            Due::Week
+        } else if days_left <= 30 {
+            Due::Month
+        } else {
+            Due::Later
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Due::Past => "Ended",
+            Due::Week => "Within a week",
+            Due::Month => "Within a month",
+            Due::Later => "Later",
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Due::Past => "bg-background-200 text-text-500",
+            Due::Week => "bg-red-500 text-text-50",
+            Due::Month => "bg-amber-400 text-text-950",
+            Due::Later => "bg-green-500 text-text-50",
+        }
+    }
+}
+
+/// Investments maturing or starting from `first` to `last`, whichever page of
+/// the list is shown.
+async fn fetch_between(first: NaiveDate, last: NaiveDate) -> Result<Vec<Investment>, ApiError> {
+    let mut investments = fetch_every_investment(&ListQuery {
+        end_from: Some(first),
+        end_to: Some(last),
+        ..ListQuery::default()
+    })
+    .await?;
+    let starting = fetch_every_investment(&ListQuery {
+        start_from: Some(first),
+        start_to: Some(last),
+        ..ListQuery::default()
+    })
+    .await?;
+    for investment in starting {
+        if !investments
+            .iter()
+            .any(|maturing| maturing.id == investment.id)
+        {
+            investments.push(investment);
+        }
+    }
+    Ok(investments)
+}
+
+/// A month of start and end dates of the investments, the end dates coloured
+/// by how soon they are due. Clicking a day shows the investments maturing
+/// on it in the list, and clicking it again shows every one.
+#[function_component(MaturityCalendar)]
+pub fn maturity_calendar(props: &MaturityCalendarProps) -> Html {
+    let today = Utc::now().date_naive();
+    let month = use_state(|| today.with_day(1).unwrap_or(today));
+
+    let go_to = |months: i32| {
+        let month = month.clone();
+        Callback::from(move |_| {
+            let next = if months < 0 {
+                month.checked_sub_months(Months::new(months.unsigned_abs()))
+            } else {
+                month.checked_add_months(Months::new(months.unsigned_abs()))
+            };
+            if let Some(next) = next {
+                month.set(next);
+            }
+        })
+    };
+
+    let first = *month;
+    let next_month = first.checked_add_months(Months::new(1)).unwrap_or(first);
+    let grid_start = first
+        .checked_sub_days(Days::new(first.weekday().num_days_from_monday().into()))
+        .unwrap_or(first);
+    let cell_count = ((next_month - grid_start).num_days() as u64).div_ceil(7) * 7;
+    let grid_end = grid_start
+        .checked_add_days(Days::new(cell_count - 1))
+        .unwrap_or(grid_start);
+
+    // only the days shown are loaded, again whenever the investments change
+    let investments = use_state(Vec::<Investment>::new);
+    {
+        let investments = investments.clone();
+        use_effect_with(
+            (grid_start, grid_end, props.changes),
+            move |(first, last, _)| {
+                let (first, last) = (*first, *last);
+                spawn_local(async move {
+                    match fetch_between(first, last).await {
+                        Ok(loaded) => investments.set(loaded),
+                        Err(e) => alert(&e.to_string()),
+                    }
+                });
+            },
+        );
+    }
+
+    let dates = |investment: &Investment| {
+        (
+            investment.start_date.map(|date| date.date_naive()),
+            investment.end_date.map(|date| date.date_naive()),
+        )
+    };
+    let maturing = investments
+        .iter()
+        .filter(|investment| matches!(dates(investment).1, Some(end) if end >= first && end < next_month))
+        .count();
+    let selected_day = match (props.query.end_from, props.query.end_to) {
+        (Some(from), Some(to)) if from == to => Some(from),
+        _ => None,
+    };
+
+    let day_cell = |day: NaiveDate| {
+        let on_click = {
+            let on_query = props.on_query.clone();
+            let day = (selected_day != Some(day)).then_some(day);
+            let query = ListQuery {
+                end_from: day,
+                end_to: day,
+                page: None,
+                ..props.query.clone()
+            };
+            Callback::from(move |_| on_query.emit(query.clone()))
+        };
+
+        let mut markers: Vec<Html> = Vec::new();
+        for investment in investments.iter() {
+            let (start, end) = dates(investment);
+            if end == Some(day) {
+                let due = Due::of(investment, day, today);
+                markers.push(html! {
+                    <span class={format!("block truncate px-1 rounded {}", due.class())} title={format!("{} of {} matures", investment.inv_name, investment.name)}>
+                        {&investment.inv_name}
+                    </span>
+                });
+            }
+            if start == Some(day) {
+                markers.push(html! {
+                    <span class="block truncate px-1 rounded border border-background-300 text-text-600" title={format!("{} of {} starts", investment.inv_name, investment.name)}>
+                        {format!("▸ {}", investment.inv_name)}
+                    </span>
+                });
+            }
+        }
+        let hidden = markers.len().saturating_sub(MAX_SHOWN_PER_DAY);
+        markers.truncate(MAX_SHOWN_PER_DAY);
+
+        let mut class = String::from("flex flex-col gap-0.5 min-h-[4.5rem] p-1 text-left text-xs border border-background-200 hover:bg-background-100");
+        if day.month() != first.month() {
+            class.push_str(" opacity-40");
+        }
+        if selected_day == Some(day) {
+            class.push_str(" ring-2 ring-primary-600");
+        }
+        let number_class = if day == today {
+            "font-bold text-primary-600"
+        } else {
+            "text-text-950"
+        };
+
+        html! {
+            <button type="button" {class} onclick={on_click}>
+                <span class={number_class}>{day.day()}</span>
+                { for markers }
+                if hidden > 0 {
+                    <span class="text-text-500">{format!("+{hidden} more")}</span>
+                }
+            </button>
+        }
+    };
+
+    html! {
+        <div class="flex flex-col gap-3 p-4 rounded-lg bg-background-50 text-text-950 text-sm">
+            <div class="flex items-center gap-2">
+                <button type="button" onclick={go_to(-1)} aria-label="Previous month" class="px-2 py-1 rounded hover:bg-background-200">{"◀"}</button>
+                <span class="font-semibold">{first.format("%B %Y").to_string()}</span>
+                <button type="button" onclick={go_to(1)} aria-label="Next month" class="px-2 py-1 rounded hover:bg-background-200">{"▶"}</button>
+                <span class="ml-auto text-text-500">{format!("{maturing} maturing")}</span>
+            </div>
+            <div class="grid grid-cols-7">
+                { for ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"].into_iter().map(|weekday| html! {
+                    <span class="px-1 pb-1 text-xs font-medium text-text-500">{weekday}</span>
+                }) }
+                { for (0..cell_count)
+                    .filter_map(|offset| grid_start.checked_add_days(Days::new(offset)))
+                    .map(day_cell) }
+            </div>
+            <div class="flex flex-wrap items-center gap-2 text-xs">
+                { for Due::ALL.into_iter().map(|due| html! {
+                    <span class={format!("px-1 rounded {}", due.class())}>{due.label()}</span>
+                }) }
+                <span class="px-1 rounded border border-background-300 text-text-600">{"▸ Starts"}</span>
+                <a href={calendar_url()} class="ml-auto hover:underline">{"Subscribe (.ics)"}</a>
+            </div>
+        </div>
+    }
+}
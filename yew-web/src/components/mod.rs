@@ -1,6 +1,15 @@
 //! This module contains various submodules related to the investment feature.
 
-///
+/// This module contains the actions applied to the selected investments.
+pub mod bulk_action_bar;
+
+/// This module contains the SVG charts of the dashboard.
+pub mod charts;
+
+/// This module contains the charts of the portfolio analytics.
+pub mod dashboard;
+
+/// This module contains the fields and validation shared by the investment forms.
 pub mod base_inv_form;
 
 /// This module contains the form used to create a new investment.
@@ -12,14 +21,38 @@ pub mod edit_inv_form;
 /// This module contains the form used to renew an existing investment.
 pub mod renew_inv_form;
 
+/// This module contains the links to download the investments as files.
+pub mod export_links;
+
 /// This module contains the table header for the investment list.
 pub mod exp_table_header;
 
+/// This module contains the upload and preview of investments imported from CSV.
+pub mod import_inv;
+
+/// This module contains the list of changes made to an investment.
+pub mod inv_history;
+
 /// This module contains the individual investment item component.
 pub mod inv_item;
 
+/// This module contains the page of a single investment.
+pub mod inv_page;
+
 /// This module contains the list of investments.
 pub mod inv_list;
 
+/// This module contains the calendar of the start and end dates of the investments.
+pub mod maturity_calendar;
+
+/// This module contains the page of the settings kept in the browser.
+pub mod settings_page;
+
+/// This module contains the renewal history of an investment.
+pub mod renewal_timeline;
+
+/// This module contains the toast offering to undo a deletion.
+pub mod undo_toast;
+
 /// This module contains the switcher component.
 pub mod switcher;
@@ -7,7 +7,8 @@ use yew::events::{Event, InputEvent};
 use yew::{html, Callback, Component, Html, Properties};
 
 use super::base_inv_form::BaseFormComponent;
-use types::{InvStatus, Investment};
+use crate::controllers::Responder;
+use types::{ApiError, Compounding, Investment, InvestmentType, ReturnType};
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct RenewInvForm {
@@ -20,7 +21,7 @@ pub struct RenewInvForm {
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct RenewInvFormProps {
-    pub renew_investment: Callback<(Investment, Investment)>,
+    pub renew_investment: Callback<(Investment, Investment, Responder)>,
     pub old_investment: Investment,
     pub on_renew: Callback<()>,
 }
@@ -28,9 +29,12 @@ pub struct RenewInvFormProps {
 pub enum Form {
     Update(String, String),
     UpdateDate(String, Option<DateTime<Utc>>),
+    /// Replaces the return amount with the computed one.
+    Calculate,
     Confirm,
     Cancel,
     Renew,
+    Response(Result<(), ApiError>),
 }
 
 impl Component for RenewInvForm {
@@ -42,19 +46,15 @@ impl Component for RenewInvForm {
             form_changed: false,
             show_renew_confirmation: false,
             renew_investment: Investment {
-                id: None,
                 inv_name: ctx.props().old_investment.inv_name.clone(),
                 name: ctx.props().old_investment.name.clone(),
-                inv_type: ctx.props().old_investment.inv_type.clone(),
-                return_type: ctx.props().old_investment.return_type.clone(),
+                institution: ctx.props().old_investment.institution.clone(),
+                inv_type: ctx.props().old_investment.inv_type,
+                return_type: ctx.props().old_investment.return_type,
+                compounding: ctx.props().old_investment.compounding,
                 inv_amount: ctx.props().old_investment.return_amount,
-                return_amount: 0,
-                return_rate: 0,
-                inv_status: None,
                 start_date: ctx.props().old_investment.end_date,
-                end_date: None,
-                created_at: None,
-                updated_at: None,
+                ..Default::default()
             },
             props: RenewInvFormProps {
                 renew_investment: ctx.props().renew_investment.clone(),
@@ -63,11 +63,12 @@ impl Component for RenewInvForm {
             },
             base: BaseFormComponent {
                 error_messages: HashMap::new(),
+                auto_return_amount: true,
             },
         }
     }
 
-    fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
         match msg {
             Form::Update(field, value) => {
                 self.base
@@ -79,11 +80,23 @@ impl Component for RenewInvForm {
                     .update_date_field(&mut self.renew_investment, &field, date);
                 self.form_changed = true;
             }
+            Form::Calculate => {
+                self.base
+                    .calculate_return_amount(&mut self.renew_investment);
+                self.form_changed = true;
+            }
             Form::Confirm => {
-                if self.save_form() {
-                    self.props.on_renew.emit(());
+                if !self.save_form(ctx) {
+                    self.show_renew_confirmation = false;
                 }
             }
+            Form::Response(Ok(())) => {
+                self.props.on_renew.emit(());
+            }
+            Form::Response(Err(error)) => {
+                self.show_renew_confirmation = false;
+                self.base.show_api_error(error);
+            }
             Form::Cancel => {
                 self.show_renew_confirmation = false;
             }
@@ -103,23 +116,17 @@ impl Component for RenewInvForm {
                         { self.date_field(ctx, "end-date", &self.renew_investment.end_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()) }
                         { self.input_field(ctx, "inv-name", "text", &self.renew_investment.inv_name) }
                         { self.input_field(ctx, "name", "text", &self.renew_investment.name) }
-                        { self.select_field(ctx, "inv-type", &self.renew_investment.inv_type,
-                            html! {
-                                <>
-                                    <option value="FD" selected={self.renew_investment.inv_type == "FD"}>{"FD"}</option>
-                                    <option value="RD" selected={self.renew_investment.inv_type == "RD"}>{"RD"}</option>
-                                </>
-                            }
+                        { self.input_field(ctx, "institution", "text", &self.renew_investment.institution) }
+                        { self.select_field(ctx, "inv-type", &self.renew_investment.inv_type.to_string(),
+                            self.base.enum_options(&InvestmentType::ALL, &self.renew_investment.inv_type)
+                        ) }
+                        { self.select_field(ctx, "return-type", &self.renew_investment.return_type.to_string(),
+                            self.base.enum_options(&ReturnType::ALL, &self.renew_investment.return_type)
                         ) }
-                        { self.select_field(ctx, "return-type", &self.renew_investment.return_type,
-                            html! {
-                                <>
-                                    <option value="Ordinary" selected={self.renew_investment.return_type == "Ordinary"}>{"Ordinary"}</option>
-                                    <option value="Culmulative" selected={self.renew_investment.return_type == "Culmulative"}>{"Culmulative"} </option>
-                                </>
-                            }
+                        { self.select_field(ctx, "compounding", &self.renew_investment.compounding().to_string(),
+                            self.base.enum_options(&Compounding::ALL, &self.renew_investment.compounding())
                         ) }
-                        { self.input_field(ctx, "return-amount", "number", &self.renew_investment.return_amount.to_string()) }
+                        { self.return_amount_field(ctx, &self.renew_investment.return_amount.to_string()) }
                         { self.input_field(ctx, "inv-amount", "number", &self.renew_investment.inv_amount.to_string()) }
                         { self.input_field(ctx, "return-rate", "number", &self.renew_investment.return_rate.to_string()) }
                         <button type="submit" disabled={!self.form_changed}
@@ -168,6 +175,18 @@ impl RenewInvForm {
             .input_field(field_id, field_type, field_value, on_input)
     }
 
+    fn return_amount_field(&self, ctx: &yew::Context<Self>, field_value: &str) -> Html {
+        let on_input = ctx.link().callback(|e: InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+            Form::Update("return-amount".to_string(), input.value())
+        });
+        self.base.return_amount_field(
+            field_value,
+            on_input,
+            ctx.link().callback(|_| Form::Calculate),
+        )
+    }
+
     fn select_field(
         &self,
         ctx: &yew::Context<Self>,
@@ -203,36 +222,17 @@ impl RenewInvForm {
         self.base.date_field(field_id, field_value, on_input)
     }
 
-    fn save_form(&mut self) -> bool {
+    fn save_form(&mut self, ctx: &yew::Context<Self>) -> bool {
         // Validate form fields
         let is_valid = self.base.validate_form(&mut self.renew_investment);
 
         if is_valid {
-            // add inv_status to renewed investment with the id of the old investment
-            // and status as "renewed"
-            self.renew_investment.inv_status = Some(InvStatus {
-                id: self.props.old_investment.id.clone(),
-                status: "renewed".to_string(),
-            });
-
-            // update the old investment with status as "closed"
-            let mut old_investment = self.props.old_investment.clone();
-            if let Some(old_status) = &old_investment.inv_status {
-                old_investment.inv_status = Some(InvStatus {
-                    id: old_status.id.clone(), // keep the id same as before
-                    status: "closed".to_string(),
-                });
-            } else {
-                // Handle the case where inv_status is None
-                old_investment.inv_status = Some(InvStatus {
-                    id: None,
-                    status: "closed".to_string(),
-                });
-            }
-
+            // the API closes the old investment and links the new one to it;
+            // the form is closed once that is done
             self.props.renew_investment.emit((
                 self.props.old_investment.clone(),
                 self.renew_investment.clone(),
+                ctx.link().callback(Form::Response),
             ));
 
             true
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with, use_state, Html, Properties};
+use yew_router::prelude::Link;
+
+use crate::inv_api::fetch_chain;
+use crate::routes::Route;
+use types::{ApiError, ChainLink, RecordId, RenewalChain};
+
+#[derive(Properties, PartialEq)]
+pub struct RenewalTimelineProps {
+    /// Investment whose renewal chain is shown.
+    pub id: RecordId,
+}
+
+/// Timeline of an investment with the ones it was renewed from and into,
+/// and the interest they earned along the way.
+#[function_component(RenewalTimeline)]
+pub fn renewal_timeline(props: &RenewalTimelineProps) -> Html {
+    let chain = use_state(|| None::<Result<RenewalChain, ApiError>>);
+
+    {
+        let chain = chain.clone();
+        use_effect_with(props.id.clone(), move |id| {
+            let id = id.clone();
+            spawn_local(async move {
+                chain.set(Some(fetch_chain(&id).await));
+            });
+        });
+    }
+
+    match &*chain {
+        None => html! { <p class="text-text-500">{"Loading history…"}</p> },
+        Some(Err(e)) => html! { <p class="text-red-600">{e.to_string()}</p> },
+        Some(Ok(chain)) => html! {
+            <>
+                <ol class="relative border-s border-background-300">
+                    { for chain.links.iter().map(|link| timeline_entry(link, &props.id)) }
+                </ol>
+                <p class="mt-2 font-medium">
+                    {format!("Total interest earned: {}", chain.total_interest_earned.to_indian_string())}
+                </p>
+            </>
+        },
+    }
+}
+
+fn timeline_entry(link: &ChainLink, current: &RecordId) -> Html {
+    let investment = &link.investment;
+    let is_current = investment.id.as_ref() == Some(current);
+    let date = |date: Option<DateTime<Utc>>| {
+        date.map(|date| date.date_naive().format("%d-%m-%Y").to_string())
+            .unwrap_or_default()
+    };
+
+    html! {
+        <li class="mb-4 ms-4">
+            <div class={if is_current { "absolute w-3 h-3 rounded-full -start-1.5 mt-1.5 bg-accent-600" } else { "absolute w-3 h-3 rounded-full -start-1.5 mt-1.5 bg-background-300" }}></div>
+            <time class="text-sm text-text-500">
+                {format!("{} – {}", date(investment.start_date), date(investment.end_date))}
+            </time>
+            <p class={if is_current { "font-medium" } else { "" }}>
+                {match &investment.id {
+                    Some(id) if !is_current => html! {
+                        <Link<Route> to={Route::investment(id)} classes="hover:underline">{investment.inv_name.clone()}</Link<Route>>
+                    },
+                    _ => html! { {&investment.inv_name} },
+                }}
+                {format!(
+                    " · {} at {}% · {}",
+                    investment.inv_amount.to_indian_string(),
+                    investment.return_rate,
+                    investment.status(),
+                )}
+            </p>
+            <p class="text-sm text-text-500">
+                {format!(
+                    "Interest earned: {} (cumulative {})",
+                    link.interest_earned.to_indian_string(),
+                    link.cumulative_interest.to_indian_string(),
+                )}
+            </p>
+        </li>
+    }
+}
@@ -0,0 +1,127 @@
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, use_state, Callback, Event, Html, TargetCast};
+
+use crate::settings::Settings;
+use types::{SortField, SortOrder, MAX_PER_PAGE};
+
+/// Page sizes offered, up to the most the API serves at once.
+const PAGE_SIZES: [u32; 4] = [10, 20, 50, MAX_PER_PAGE];
+
+const SORT_FIELDS: [(SortField, &str); 10] = [
+    (SortField::CreatedAt, "Date added"),
+    (SortField::StartDate, "Start Date"),
+    (SortField::EndDate, "End Date"),
+    (SortField::InvName, "Investment Name"),
+    (SortField::Name, "Name"),
+    (SortField::InvType, "Investment Type"),
+    (SortField::ReturnType, "Return Type"),
+    (SortField::ReturnRate, "Return Rate"),
+    (SortField::InvAmount, "Investment"),
+    (SortField::ReturnAmount, "Return"),
+];
+
+const SORT_ORDERS: [(SortOrder, &str); 2] = [
+    (SortOrder::Asc, "Ascending"),
+    (SortOrder::Desc, "Descending"),
+];
+
+/// Whether the settings shown were saved, or why they could not be.
+#[derive(Clone, PartialEq)]
+enum Saved {
+    No,
+    Yes,
+    Failed(String),
+}
+
+/// Preferences of the list of investments, saved in the browser.
+#[function_component(SettingsPage)]
+pub fn settings_page() -> Html {
+    let settings = use_state(Settings::load);
+    let saved = use_state(|| Saved::No);
+
+    let update = |change: fn(&mut Settings, &str)| {
+        let settings = settings.clone();
+        let saved = saved.clone();
+        Callback::from(move |e: Event| {
+            let value = e.target_unchecked_into::<HtmlSelectElement>().value();
+            let mut next = (*settings).clone();
+            change(&mut next, &value);
+            settings.set(next);
+            saved.set(Saved::No);
+        })
+    };
+
+    let on_save = {
+        let settings = settings.clone();
+        let saved = saved.clone();
+        Callback::from(move |_| {
+            saved.set(match settings.save() {
+                Ok(()) => Saved::Yes,
+                Err(e) => Saved::Failed(e.to_string()),
+            });
+        })
+    };
+
+    let on_reset = {
+        let settings = settings.clone();
+        let saved = saved.clone();
+        Callback::from(move |_| {
+            settings.set(Settings::default());
+            saved.set(Saved::No);
+        })
+    };
+
+    let select_class = "border border-background-300 text-text-950 text-sm rounded-lg block w-full p-2.5 bg-background-50";
+
+    html! {
+        <div class="flex flex-col gap-6 max-w-xl p-4 rounded-lg bg-background-50 text-text-950">
+            <div class="grid gap-6 md:grid-cols-3">
+                <div>
+                    <label for="per-page" class="block mb-2 text-sm font-medium">{"Rows per page"}</label>
+                    <select id="per-page" class={select_class} onchange={update(|settings, value| {
+                        if let Ok(per_page) = value.parse() {
+                            settings.per_page = per_page;
+                        }
+                    })}>
+                        { for PAGE_SIZES.into_iter().map(|size| html! {
+                            <option value={size.to_string()} selected={settings.per_page == size}>{size}</option>
+                        }) }
+                    </select>
+                </div>
+                <div>
+                    <label for="sort" class="block mb-2 text-sm font-medium">{"Sort by"}</label>
+                    <select id="sort" class={select_class} onchange={update(|settings, value| {
+                        if let Some((sort, _)) = SORT_FIELDS.into_iter().find(|(sort, _)| sort.column() == value) {
+                            settings.sort = sort;
+                        }
+                    })}>
+                        { for SORT_FIELDS.into_iter().map(|(sort, label)| html! {
+                            <option value={sort.column()} selected={settings.sort == sort}>{label}</option>
+                        }) }
+                    </select>
+                </div>
+                <div>
+                    <label for="order" class="block mb-2 text-sm font-medium">{"Order"}</label>
+                    <select id="order" class={select_class} onchange={update(|settings, value| {
+                        if let Some((order, _)) = SORT_ORDERS.into_iter().find(|(order, _)| order.to_string() == value) {
+                            settings.order = order;
+                        }
+                    })}>
+                        { for SORT_ORDERS.into_iter().map(|(order, label)| html! {
+                            <option value={order.to_string()} selected={settings.order == order}>{label}</option>
+                        }) }
+                    </select>
+                </div>
+            </div>
+            <div class="flex items-center gap-4">
+                <button type="button" onclick={on_save} class="px-5 py-2.5 text-sm font-medium text-text-50 rounded-lg bg-primary-600 hover:bg-primary-700">{"Save"}</button>
+                <button type="button" onclick={on_reset} class="px-5 py-2.5 text-sm font-medium text-text-50 rounded-lg bg-background-500">{"Reset to defaults"}</button>
+                {match &*saved {
+                    Saved::No => html! {},
+                    Saved::Yes => html! { <span class="text-sm text-green-600">{"Saved. The list uses them from now on."}</span> },
+                    Saved::Failed(error) => html! { <span class="text-sm text-red-600">{format!("Could not save the settings: {error}")}</span> },
+                }}
+            </div>
+        </div>
+    }
+}
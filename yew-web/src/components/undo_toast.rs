@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use wasm_bindgen_futures::spawn_local;
+use yew::platform::time::sleep;
+use yew::{function_component, html, use_effect_with, Callback, Html, Properties};
+
+use types::RecordId;
+
+/// How long the toast stays up before the deletion is final.
+const UNDO_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Properties, PartialEq)]
+pub struct UndoToastProps {
+    /// Investment that was just moved to the trash.
+    pub id: RecordId,
+    pub inv_name: String,
+    pub on_undo: Callback<RecordId>,
+    pub on_dismiss: Callback<RecordId>,
+}
+
+/// Toast shown after deleting an investment, offering to restore it.
+#[function_component(UndoToast)]
+pub fn undo_toast(props: &UndoToastProps) -> Html {
+    let id = props.id.clone();
+
+    {
+        let on_dismiss = props.on_dismiss.clone();
+        use_effect_with(id.clone(), move |id| {
+            let id = id.clone();
+            spawn_local(async move {
+                sleep(UNDO_TIMEOUT).await;
+                on_dismiss.emit(id);
+            });
+        });
+    }
+
+    let on_undo = {
+        let on_undo = props.on_undo.clone();
+        let id = id.clone();
+        Callback::from(move |_| on_undo.emit(id.clone()))
+    };
+    let on_dismiss = {
+        let on_dismiss = props.on_dismiss.clone();
+        Callback::from(move |_| on_dismiss.emit(id.clone()))
+    };
+
+    html! {
+        <div class="fixed bottom-6 left-1/2 -translate-x-1/2 z-50 flex items-center gap-4 px-4 py-3 rounded-lg shadow-lg bg-background-900 text-text-50" role="status">
+            <span>{format!("Deleted \"{}\"", props.inv_name)}</span>
+            <button class="font-semibold text-accent-400 hover:underline" onclick={on_undo}>{"Undo"}</button>
+            <button class="text-text-300 hover:text-text-50" aria-label="Dismiss" onclick={on_dismiss}>{"✕"}</button>
+        </div>
+    }
+}
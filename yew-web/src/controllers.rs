@@ -1,40 +1,45 @@
 use gloo_dialogs::alert;
-use serde::{Deserialize, Serialize};
-use surrealdb::sql::{Id, Thing};
 use wasm_bindgen_futures::spawn_local;
-use yew::UseReducerHandle;
+use yew::{Callback, UseReducerHandle};
 
 use crate::{inv_api::*, state::*};
 use types::*;
 
+/// Tells the form that issued a request whether it succeeded, so that it can
+/// reset itself or show the errors returned by the API.
+pub type Responder = Callback<Result<(), ApiError>>;
+
+/// Gets the report of an import, the preview of a dry run included.
+pub type ImportResponder = Callback<Result<ImportReport, ApiError>>;
+
+/// Gets an investment as it was loaded from the API.
+pub type InvestmentResponder = Callback<Result<Investment, ApiError>>;
+
 pub struct InvestmentController {
     state: UseReducerHandle<InvestmentState>,
 }
 
-#[derive(Serialize, Deserialize)]
-pub struct TempThing {
-    pub tb: String,
-    pub id: Id,
-}
-
 impl InvestmentController {
     pub fn new(state: UseReducerHandle<InvestmentState>) -> InvestmentController {
         InvestmentController { state }
     }
 
-    pub fn init_investments(&self) {
+    /// Loads the page of investments matching `query`.
+    pub fn load_investments(&self, query: ListQuery) {
         let investments = self.state.clone();
 
         spawn_local(async move {
-            let fetched_investments = fetch_investments().await;
+            let fetched_investments = fetch_investments(&query).await;
             match fetched_investments {
-                Ok(ft) => investments.dispatch(InvestmentAction::Set(ft)),
+                Ok(page) => investments.dispatch(InvestmentAction::Set(query, page)),
                 Err(e) => alert(&e.to_string()),
             }
+
+            refresh_summary(&investments).await;
         });
     }
 
-    pub fn create_investment(&self, inv: Investment) {
+    pub fn create_investment(&self, inv: Investment, responder: Responder) {
         let investments = self.state.clone();
 
         spawn_local(async move {
@@ -42,140 +47,211 @@ impl InvestmentController {
             let response = create_investment(inv.to_string()).await;
 
             match response {
-                Ok(investment) => investments.dispatch(InvestmentAction::Add(investment)),
-                Err(e) => alert(&e.to_string()),
+                Ok(investment) => {
+                    investments.dispatch(InvestmentAction::Add(investment));
+                    responder.emit(Ok(()));
+                }
+                Err(e) => return responder.emit(Err(e)),
             }
+
+            refresh_overview(&investments).await;
         });
     }
 
-    pub fn edit_investment(&self, inv: Investment) {
+    /// Sends the fields of `inv` that differ from the loaded investment. The
+    /// update fails with a conflict if someone changed it in the meantime.
+    pub fn edit_investment(&self, inv: Investment, responder: Responder) {
         let investments = self.state.clone();
 
         spawn_local(async move {
-            // this is a workaround for the fact that we can't serialize a Thing
-            // directly. We need to serialize a struct that contains the Thing's
-            // table name and id and then insert that into the Investment object
-            // and then serialize the Investment object.
-            // TODO: update surrealdb to allow serialization of a Thing after figuring out dependency issues
-            let temp_id = match inv.id.clone() {
-                Some(id) => serialize_thing(id),
-                None => TempThing {
-                    tb: "".to_string(),
-                    id: Id::Number(0),
-                },
+            let Some(id) = inv.id.clone() else {
+                return responder.emit(Err(ApiError::new(
+                    0,
+                    "request",
+                    "the investment to edit has no id",
+                )));
             };
-            let temp_id = serde_json::json!(temp_id);
-            let mut inv = serde_json::json!(inv);
-
-            if let Some(obj1) = inv.as_object_mut() {
-                obj1.insert("id".to_string(), temp_id.clone());
+            let loaded = investments
+                .investments
+                .iter()
+                .find(|loaded| loaded.id == inv.id)
+                .cloned();
+            let mut patch = serde_json::Map::new();
+            for change in FieldChange::diff(loaded.as_ref(), &inv) {
+                patch.insert(change.field, change.to);
             }
-            let response = edit_investment(inv.to_string()).await;
+
+            let patch = serde_json::Value::Object(patch);
+            let response = edit_investment(&id, patch.to_string(), inv.version).await;
 
             match response {
-                Ok(investment) => investments.dispatch(InvestmentAction::Edit(investment)),
-                Err(e) => alert(&e.to_string()),
+                Ok(investment) => {
+                    investments.dispatch(InvestmentAction::Edit(investment));
+                    responder.emit(Ok(()));
+                }
+                Err(e) => return responder.emit(Err(e)),
             }
+
+            refresh_overview(&investments).await;
         });
     }
 
-    pub fn delete_investment(&self, id: Thing) {
+    /// Loads the latest version of an investment, replacing the one in the
+    /// list so that later edits are compared against it.
+    pub fn reload_investment(&self, id: RecordId, responder: InvestmentResponder) {
         let investments = self.state.clone();
 
         spawn_local(async move {
-            // temp_id is a workaround for the fact that we can't serialize a Thing
-            // directly. We need to serialize a struct that contains the Thing's
-            // table name and id.
-            // TODO: update surrealdb to allow serialization of a Thing after figuring out dependency issues
-            let temp_id = serialize_thing(id.clone());
-            let json_id = serde_json::json!(temp_id);
-            let response = delete_investment(json_id.to_string()).await;
+            let response = fetch_investment(&id).await;
+            if let Ok(investment) = &response {
+                investments.dispatch(InvestmentAction::Edit(investment.clone()));
+            }
+            responder.emit(response);
+        });
+    }
+
+    /// Applies `operations` all together, then reloads the current page. If
+    /// any of them can not be applied, none is and the responder gets why.
+    pub fn bulk_investments(&self, operations: Vec<BulkOperation>, responder: Responder) {
+        let investments = self.state.clone();
+
+        spawn_local(async move {
+            let response = bulk_investments(&BulkRequest { operations }).await;
 
             match response {
-                Ok(af) if af.id == id => investments.dispatch(InvestmentAction::Delete(id.clone())),
-                Ok(_) => alert("Did not get a response"),
+                Ok(report) if report.applied => responder.emit(Ok(())),
+                Ok(report) => {
+                    let failures = report
+                        .errors()
+                        .filter_map(|result| {
+                            let error = result.error.as_ref()?;
+                            Some(match &result.id {
+                                Some(id) => format!("{id}: {error}"),
+                                None => format!("#{}: {error}", result.index + 1),
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    return responder.emit(Err(ApiError::new(
+                        422,
+                        "bulk",
+                        format!("Nothing was changed:\n{failures}"),
+                    )));
+                }
+                Err(e) => return responder.emit(Err(e)),
+            }
+
+            let query = investments.query.clone();
+            match fetch_investments(&query).await {
+                Ok(page) => investments.dispatch(InvestmentAction::Set(query, page)),
                 Err(e) => alert(&e.to_string()),
             }
+
+            refresh_overview(&investments).await;
         });
     }
 
-    pub fn renew_investment(&self, old_inv: Investment, renew_inv: Investment) {
+    /// Checks or imports the rows of a CSV file, then reloads the current
+    /// page if any investment was imported.
+    pub fn import_investments(&self, request: ImportRequest, responder: ImportResponder) {
         let investments = self.state.clone();
 
         spawn_local(async move {
-            // add renew investment
-
-            // this is a workaround for the fact that we can't serialize a Thing
-            // directly. We need to serialize a struct that contains the Thing's
-            // table name and id and then insert that into the Investment object
-            // and then serialize the Investment object.
-            // TODO: update surrealdb to allow serialization of a Thing after figuring out dependency issues
-            let inv_status = match renew_inv.inv_status.clone() {
-                Some(inv_status) => inv_status.id.clone(),
-                None => None,
-            };
-            let temp_id = match inv_status {
-                Some(id) => serialize_thing(id),
-                None => TempThing {
-                    tb: "".to_string(),
-                    id: Id::Number(0),
-                },
-            };
-            let temp_id = serde_json::json!(temp_id);
-
-            let mut inv_status = serde_json::json!(renew_inv.inv_status);
+            let response = import_investments(&request).await;
+            let imported = matches!(&response, Ok(report) if report.imported > 0);
+            responder.emit(response);
+            if !imported {
+                return;
+            }
 
-            if let Some(obj1) = inv_status.as_object_mut() {
-                obj1.insert("id".to_string(), temp_id.clone());
+            let query = investments.query.clone();
+            match fetch_investments(&query).await {
+                Ok(page) => investments.dispatch(InvestmentAction::Set(query, page)),
+                Err(e) => alert(&e.to_string()),
             }
 
-            let mut renew_inv = serde_json::json!(renew_inv);
+            refresh_overview(&investments).await;
+        });
+    }
+
+    pub fn delete_investment(&self, id: RecordId) {
+        let investments = self.state.clone();
+
+        spawn_local(async move {
+            let response = delete_investment(&id).await;
 
-            if let Some(obj1) = renew_inv.as_object_mut() {
-                obj1.insert("inv_status".to_string(), inv_status.clone());
+            match response {
+                Ok(af) if af.id == id => investments.dispatch(InvestmentAction::Delete(id.clone())),
+                Ok(_) => alert("Did not get a response"),
+                Err(e) => alert(&e.to_string()),
             }
 
-            log::info!("inv: {}", renew_inv);
-            let response = create_investment(renew_inv.to_string()).await;
+            refresh_overview(&investments).await;
+        });
+    }
+
+    /// Takes a deleted investment out of the trash and back into the list.
+    pub fn restore_investment(&self, id: RecordId) {
+        let investments = self.state.clone();
+
+        spawn_local(async move {
+            investments.dispatch(InvestmentAction::DismissUndo(id.clone()));
+            let response = restore_investment(&id).await;
 
             match response {
                 Ok(investment) => investments.dispatch(InvestmentAction::Add(investment)),
                 Err(e) => alert(&e.to_string()),
             }
 
-            // update old investment
-
-            // this is a workaround for the fact that we can't serialize a Thing
-            // directly. We need to serialize a struct that contains the Thing's
-            // table name and id and then insert that into the Investment object
-            // and then serialize the Investment object.
-            // TODO: update surrealdb to allow serialization of a Thing after figuring out dependency issues
-            let temp_id = match old_inv.id.clone() {
-                Some(id) => serialize_thing(id),
-                None => TempThing {
-                    tb: "".to_string(),
-                    id: Id::Number(0),
-                },
-            };
-            let temp_id = serde_json::json!(temp_id);
-            let mut old_inv = serde_json::json!(old_inv);
+            refresh_overview(&investments).await;
+        });
+    }
 
-            if let Some(obj1) = old_inv.as_object_mut() {
-                obj1.insert("id".to_string(), temp_id.clone());
-            }
-            let response = edit_investment(old_inv.to_string()).await;
+    /// Closes `old_inv` and creates `renew_inv` in its place, both or neither.
+    pub fn renew_investment(
+        &self,
+        old_inv: Investment,
+        renew_inv: Investment,
+        responder: Responder,
+    ) {
+        let investments = self.state.clone();
+
+        spawn_local(async move {
+            let Some(id) = old_inv.id else {
+                return responder.emit(Err(ApiError::new(
+                    0,
+                    "request",
+                    "the investment to renew has no id",
+                )));
+            };
+            let renew_inv = serde_json::json!(renew_inv);
+            let response = renew_investment(&id, renew_inv.to_string()).await;
 
             match response {
-                Ok(investment) => investments.dispatch(InvestmentAction::Edit(investment)),
-                Err(e) => alert(&e.to_string()),
+                Ok(renewal) => {
+                    investments.dispatch(InvestmentAction::Edit(renewal.previous));
+                    investments.dispatch(InvestmentAction::Add(renewal.renewed));
+                    responder.emit(Ok(()));
+                }
+                Err(e) => return responder.emit(Err(e)),
             }
+
+            refresh_overview(&investments).await;
         });
     }
 }
 
-fn serialize_thing(thing: Thing) -> TempThing {
-    TempThing {
-        tb: thing.tb,
-        id: thing.id,
+/// Reloads the portfolio totals shown in the header.
+async fn refresh_summary(investments: &UseReducerHandle<InvestmentState>) {
+    match fetch_summary().await {
+        Ok(summary) => investments.dispatch(InvestmentAction::SetSummary(summary)),
+        Err(e) => alert(&e.to_string()),
     }
 }
+
+/// Reloads what is shown of every investment after they change, not only the
+/// current page: the summary and the calendar.
+async fn refresh_overview(investments: &UseReducerHandle<InvestmentState>) {
+    investments.dispatch(InvestmentAction::Changed);
+    refresh_summary(investments).await;
+}
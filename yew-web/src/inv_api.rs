@@ -1,55 +1,198 @@
-use std::collections::VecDeque;
+use std::fmt::Display;
 
-use reqwasm::{http::Request, Error};
+use reqwasm::http::{Request, Response};
+use serde::de::DeserializeOwned;
 
 use types::*;
 
 #[allow(dead_code)]
 const BASE_URL: &str = "http://localhost:8080";
 
-pub async fn fetch_investments() -> Result<VecDeque<Investment>, Error> {
-    let response = Request::get(&format!("{BASE_URL}/invs")).send().await?;
-    response.json().await
+pub async fn fetch_investments(query: &ListQuery) -> Result<Page<Investment>, ApiError> {
+    let query = serde_urlencoded::to_string(query).unwrap_or_default();
+    let response = Request::get(&format!("{BASE_URL}/invs?{query}"))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
+
+/// Link to download every investment matching the filters of `query`, in
+/// its order, as a file in `format`.
+pub fn export_url(query: &ListQuery, format: ExportFormat) -> String {
+    let query = ListQuery {
+        page: None,
+        per_page: None,
+        ..query.clone()
+    };
+    let query = serde_urlencoded::to_string(query).unwrap_or_default();
+    let separator = if query.is_empty() { "" } else { "&" };
+
+    format!(
+        "{BASE_URL}/invs/export?{query}{separator}format={format}",
+        format = format.as_str()
+    )
+}
+
+/// Every investment matching the filters of `query` at once, for views that
+/// show all of them in a range of dates.
+pub async fn fetch_every_investment(query: &ListQuery) -> Result<Vec<Investment>, ApiError> {
+    let response = Request::get(&export_url(query, ExportFormat::Json))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
+
+/// Link to the iCalendar feed of the end dates of every investment.
+pub fn calendar_url() -> String {
+    format!("{BASE_URL}/invs/calendar.ics")
+}
+
+pub async fn fetch_summary() -> Result<PortfolioSummary, ApiError> {
+    let response = Request::get(&format!("{BASE_URL}/invs/summary"))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
 }
 
-pub async fn create_investment(inv: String) -> Result<Investment, Error> {
+pub async fn fetch_analytics() -> Result<PortfolioAnalytics, ApiError> {
+    let response = Request::get(&format!("{BASE_URL}/invs/analytics"))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
+
+pub async fn create_investment(inv: String) -> Result<Investment, ApiError> {
     let response = Request::post(&format!("{}/inv", BASE_URL))
         .header("Content-Type", "application/json")
         .body(inv) // Set the serialized JSON as the body
         .send()
-        .await?;
+        .await
+        .map_err(request_error)?;
 
-    // Log the response body
-    let response_body = response.text().await?;
+    parse_response(response).await
+}
 
-    // Parse the response body as JSON
-    let inv: Investment = serde_json::from_str(&response_body)?;
+pub async fn fetch_investment(id: &RecordId) -> Result<Investment, ApiError> {
+    let response = Request::get(&format!("{BASE_URL}/inv/{id}"))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
 
-    Ok(inv)
+/// Sends a JSON merge patch, which only goes through if the investment is
+/// still at `version`.
+pub async fn edit_investment(
+    id: &RecordId,
+    patch: String,
+    version: u64,
+) -> Result<Investment, ApiError> {
+    let response = Request::patch(&format!("{BASE_URL}/inv/{id}"))
+        .header("Content-Type", "application/merge-patch+json")
+        .header("If-Match", &format!("\"{version}\""))
+        .body(patch) // Set the serialized JSON as the body
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
 }
 
-pub async fn edit_investment(inv: String) -> Result<Investment, Error> {
-    let response = Request::patch(&format!("{}/inv", BASE_URL))
+pub async fn delete_investment(id: &RecordId) -> Result<Record, ApiError> {
+    let response = Request::delete(&format!("{BASE_URL}/inv/{id}"))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
+
+pub async fn restore_investment(id: &RecordId) -> Result<Investment, ApiError> {
+    let response = Request::post(&format!("{BASE_URL}/inv/{id}/restore"))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
+
+pub async fn bulk_investments(request: &BulkRequest) -> Result<BulkReport, ApiError> {
+    let body = serde_json::to_string(request).map_err(request_error)?;
+    let response = Request::post(&format!("{BASE_URL}/invs/bulk"))
         .header("Content-Type", "application/json")
-        .body(inv) // Set the serialized JSON as the body
+        .body(body)
         .send()
-        .await?;
+        .await
+        .map_err(request_error)?;
 
-    // Log the response body
-    let response_body = response.text().await?;
+    parse_response(response).await
+}
 
-    // Parse the response body as JSON
-    let inv: Investment = serde_json::from_str(&response_body)?;
+pub async fn import_investments(request: &ImportRequest) -> Result<ImportReport, ApiError> {
+    let body = serde_json::to_string(request).map_err(request_error)?;
+    let response = Request::post(&format!("{BASE_URL}/invs/import"))
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(request_error)?;
 
-    Ok(inv)
+    parse_response(response).await
 }
 
-pub async fn delete_investment(id: String) -> Result<Record, Error> {
-    let response = Request::delete(&format!("{}/inv", BASE_URL))
+pub async fn fetch_chain(id: &RecordId) -> Result<RenewalChain, ApiError> {
+    let response = Request::get(&format!("{BASE_URL}/inv/{id}/chain"))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
+
+pub async fn fetch_history(id: &RecordId) -> Result<Vec<AuditEntry>, ApiError> {
+    let response = Request::get(&format!("{BASE_URL}/inv/{id}/history"))
+        .send()
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
+
+pub async fn renew_investment(id: &RecordId, inv: String) -> Result<Renewal, ApiError> {
+    let response = Request::post(&format!("{BASE_URL}/inv/{id}/renew"))
         .header("Content-Type", "application/json")
-        .body(id) // Set the serialized JSON as the body
+        .body(inv) // Set the serialized JSON as the body
         .send()
-        .await?;
+        .await
+        .map_err(request_error)?;
+
+    parse_response(response).await
+}
+
+/// Parses the body of a successful response as `T`, and the body of a failed
+/// one as the [`ApiError`] the API sends back.
+async fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, ApiError> {
+    let response_body = response.text().await.map_err(request_error)?;
+
+    if response.ok() {
+        serde_json::from_str(&response_body).map_err(request_error)
+    } else {
+        // errors that do not come from our handlers (e.g. a proxy) have no JSON body
+        Err(serde_json::from_str(&response_body)
+            .unwrap_or_else(|_| ApiError::new(response.status(), "http", response_body)))
+    }
+}
 
-    response.json().await
+/// Wraps an error that happened before getting an answer from the API.
+fn request_error(error: impl Display) -> ApiError {
+    ApiError::new(0, "request", error.to_string())
 }
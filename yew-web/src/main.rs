@@ -2,6 +2,8 @@ mod app;
 mod components;
 mod controllers;
 mod inv_api;
+mod routes;
+mod settings;
 mod state;
 
 use app::App;
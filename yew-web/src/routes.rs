@@ -0,0 +1,37 @@
+use yew_router::Routable;
+
+use types::RecordId;
+
+/// Table the investments are stored in, whose name is left out of their URLs.
+const INVESTMENT_TABLE: &str = "investment";
+
+/// Pages of the app, each at a URL that can be shared or bookmarked.
+#[derive(Clone, Routable, PartialEq, Debug)]
+pub enum Route {
+    /// The list of investments, filtered by the query string of the URL.
+    #[at("/")]
+    Investments,
+    /// One investment, by the key of its record id.
+    #[at("/investment/:id")]
+    Investment { id: String },
+    #[at("/dashboard")]
+    Dashboard,
+    #[at("/settings")]
+    Settings,
+    #[not_found]
+    #[at("/404")]
+    NotFound,
+}
+
+impl Route {
+    /// Page of the investment with the id `id`, like `/investment/abc` for
+    /// `investment:abc`.
+    pub fn investment(id: &RecordId) -> Route {
+        Route::Investment { id: id.id.clone() }
+    }
+
+    /// Record id of the investment of an [`Route::Investment`] page.
+    pub fn investment_id(key: &str) -> RecordId {
+        RecordId::new(INVESTMENT_TABLE, key)
+    }
+}
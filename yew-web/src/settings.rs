@@ -0,0 +1,54 @@
+use gloo_storage::{errors::StorageError, LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+use types::{ListQuery, SortField, SortOrder, DEFAULT_PER_PAGE};
+
+/// Key the settings are kept under in the local storage of the browser.
+const STORAGE_KEY: &str = "mone-goblin.settings";
+
+/// Preferences of the user, kept in the browser between visits.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Investments shown per page of the list.
+    pub per_page: u32,
+    /// Order of the list when none was picked from its headers.
+    pub sort: SortField,
+    pub order: SortOrder,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            per_page: DEFAULT_PER_PAGE,
+            sort: SortField::CreatedAt,
+            order: SortOrder::Desc,
+        }
+    }
+}
+
+impl Settings {
+    /// The saved settings, or the default ones if none were saved or they
+    /// can not be read.
+    pub fn load() -> Settings {
+        LocalStorage::get(STORAGE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), StorageError> {
+        LocalStorage::set(STORAGE_KEY, self)
+    }
+
+    /// Fills in the page size and order `query` leaves out.
+    pub fn apply(&self, query: ListQuery) -> ListQuery {
+        let (sort, order) = match query.sort {
+            Some(sort) => (Some(sort), query.order),
+            None => (Some(self.sort), Some(self.order)),
+        };
+        ListQuery {
+            per_page: query.per_page.or(Some(self.per_page)),
+            sort,
+            order,
+            ..query
+        }
+    }
+}
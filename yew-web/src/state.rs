@@ -1,22 +1,39 @@
 use std::collections::VecDeque;
 use std::rc::Rc;
 
-use surrealdb::sql::Thing;
 use yew::Reducible;
 
-use types::Investment;
+use types::{Investment, ListQuery, Page, PortfolioSummary, RecordId};
 
 /// reducer's Action
 pub enum InvestmentAction {
-    Set(VecDeque<Investment>),
+    /// A page of investments was loaded for the given query.
+    Set(ListQuery, Page<Investment>),
     Add(Investment),
     Edit(Investment),
-    Delete(Thing),
+    Delete(RecordId),
+    /// The investment no longer needs to be offered for undoing its deletion.
+    DismissUndo(RecordId),
+    SetSummary(PortfolioSummary),
+    /// Investments were created, changed or deleted.
+    Changed,
 }
 
 /// reducer's State
+#[derive(Clone)]
 pub struct InvestmentState {
+    /// Investments of the current page.
     pub investments: VecDeque<Investment>,
+    /// Query the current page was loaded with.
+    pub query: ListQuery,
+    /// Number of investments matching the query, across all pages.
+    pub total: u64,
+    pub summary: Option<PortfolioSummary>,
+    /// Bumped whenever investments change, so that views loading investments
+    /// of their own, like the calendar, load them again.
+    pub changes: u32,
+    /// Last deleted investment, which can be restored from the trash.
+    pub deleted: Option<Investment>,
 }
 
 /// Implementation by default when starting the application
@@ -24,6 +41,11 @@ impl Default for InvestmentState {
     fn default() -> Self {
         Self {
             investments: VecDeque::from([]),
+            query: ListQuery::default(),
+            total: 0,
+            summary: None,
+            changes: 0,
+            deleted: None,
         }
     }
 }
@@ -34,33 +56,55 @@ impl Reducible for InvestmentState {
     type Action = InvestmentAction;
 
     fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
-        let next_investments = match action {
-            InvestmentAction::Set(investments) => investments,
+        let mut next = (*self).clone();
+
+        match action {
+            InvestmentAction::Set(query, page) => {
+                next.investments = page.items.into();
+                next.query = query;
+                next.total = page.total;
+            }
             InvestmentAction::Add(investment) => {
-                let mut investments = self.investments.clone();
-                investments.push_front(investment);
-                investments
+                next.investments.push_front(investment);
+                next.total += 1;
             }
             InvestmentAction::Edit(edited_inv) => {
-                let mut investments = self.investments.clone();
-                if let Some(investment) = investments
+                if let Some(investment) = next
+                    .investments
                     .iter_mut()
                     .find(|investment| investment.id == edited_inv.id)
                 {
                     *investment = edited_inv.clone();
                 }
-                investments
             }
             InvestmentAction::Delete(id) => {
-                let mut investments = self.investments.clone();
-                investments.retain(|investment| investment.id != Some(id.clone()));
-                investments
+                if let Some(index) = next
+                    .investments
+                    .iter()
+                    .position(|investment| investment.id.as_ref() == Some(&id))
+                {
+                    next.deleted = next.investments.remove(index);
+                }
+                next.total = next.total.saturating_sub(1);
+            }
+            InvestmentAction::DismissUndo(id) => {
+                // a newer deletion may have taken its place
+                if next
+                    .deleted
+                    .as_ref()
+                    .is_some_and(|deleted| deleted.id.as_ref() == Some(&id))
+                {
+                    next.deleted = None;
+                }
+            }
+            InvestmentAction::SetSummary(summary) => {
+                next.summary = Some(summary);
+            }
+            InvestmentAction::Changed => {
+                next.changes = next.changes.wrapping_add(1);
             }
-        };
-
-        Self {
-            investments: next_investments,
         }
-        .into()
+
+        next.into()
     }
 }